@@ -1,24 +1,46 @@
 use crate::clipboard;
-use crate::dictionary::{fix_transcription_with_dictionary, get_cc_rules_path, Dictionary};
+use crate::dictionary::{
+    confident_words, fix_transcription_with_dictionary, get_cc_rules_path, Dictionary,
+};
+use crate::disfluency;
 use crate::engine::{
-    engine::ParakeetEngine, engine::ParakeetModelParams, transcription_engine::TranscriptionEngine,
+    create_engine,
+    engine::{OptimizationLevel, ParakeetModelParams, TimestampGranularity},
+    transcription_engine::{DynTranscriptionEngine, TranscriptionResult},
 };
+use crate::error::{self, MurmureError};
 use crate::history;
+use crate::itn;
+use crate::language;
+use crate::live_preview;
 use crate::model::Model;
 use crate::overlay;
+use crate::profiles::{self, OutputProfile};
+use crate::settings::AppSettings;
+use crate::transcription::{self, TranscriptionState};
 use anyhow::{Context, Result};
 use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
 use hound::{WavSpec, WavWriter};
 use once_cell::sync::Lazy;
 use parking_lot::Mutex;
+use serde::Serialize;
+use std::collections::VecDeque;
 use std::fs::File;
 use std::io::BufWriter;
 use std::path::PathBuf;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 use tauri::AppHandle;
 use tauri::Emitter;
 use tauri::Manager;
 
+/// Clips transcribed together by `transcribe_files`
+const MAX_BATCH_CLIPS: usize = 8;
+/// Padded samples per batch, longer clips are transcribed in smaller batches
+const MAX_BATCH_SAMPLES: usize = 16000 * 60 * 10;
+/// How long the cancelled state stays visible before the overlay hides
+const CANCELLED_OVERLAY_DURATION: Duration = Duration::from_millis(1500);
+
 type WavWriterType = WavWriter<BufWriter<File>>;
 type RecorderType = Mutex<Option<WavWriterType>>;
 
@@ -28,51 +50,90 @@ static STREAM: Lazy<parking_lot::Mutex<Option<cpal::Stream>>> =
     Lazy::new(|| parking_lot::Mutex::new(None));
 static CURRENT_FILE_NAME: Lazy<parking_lot::Mutex<Option<String>>> =
     Lazy::new(|| parking_lot::Mutex::new(None));
-static ENGINE: Lazy<parking_lot::Mutex<Option<ParakeetEngine>>> =
+/// Kept on disk so it can be transcribed again
+static LAST_RECORDING: Lazy<parking_lot::Mutex<Option<PathBuf>>> =
+    Lazy::new(|| parking_lot::Mutex::new(None));
+static ENGINE: Lazy<parking_lot::Mutex<Option<Box<dyn DynTranscriptionEngine>>>> =
+    Lazy::new(|| parking_lot::Mutex::new(None));
+static LOADED_MODEL: Lazy<parking_lot::Mutex<Option<LoadedModelInfo>>> =
+    Lazy::new(|| parking_lot::Mutex::new(None));
+/// When the engine was last loaded or used, for the idle unload
+static ENGINE_LAST_USED: Lazy<parking_lot::Mutex<Instant>> =
+    Lazy::new(|| parking_lot::Mutex::new(Instant::now()));
+/// Only kept while recording with the expanded overlay
+static LIVE_AUDIO: Lazy<parking_lot::Mutex<Option<Arc<LiveAudio>>>> =
     Lazy::new(|| parking_lot::Mutex::new(None));
 
+/// What the engine actually loaded, since a missing INT8 file falls back to FP32.
+/// Payload of the "model-loaded" event.
+#[derive(Serialize, Clone, Debug)]
+pub struct LoadedModelInfo {
+    pub engine: String,
+    pub path: String,
+    /// Requested precision, "int8" | "fp32"
+    pub precision: String,
+    pub onnx_files: Vec<String>,
+}
+
+/// Mono samples of the end of the recording in progress, for the live preview.
+/// Only the last `live_preview::PARTIAL_WINDOW_SECONDS` are kept, the WAV file has the rest.
+struct LiveAudio {
+    samples: Mutex<VecDeque<f32>>,
+    capacity: usize,
+    sample_rate: u32,
+}
+
 pub fn record_audio(app: &tauri::AppHandle) {
-    println!("Starting audio recording...");
+    log::info!("Starting audio recording...");
 
     if RECORDER.lock().is_some() {
-        println!("Already recording");
+        log::info!("Already recording");
         return;
     }
 
-    let recordings_dir = match ensure_recordings_dir(app) {
-        Ok(dir) => dir,
-        Err(e) => {
-            eprintln!("Failed to initialize recordings directory: {}", e);
-            return;
+    if let Err(e) = start_recording(app) {
+        // Leave nothing behind that would block the next recording
+        STREAM.lock().take();
+        RECORDER.lock().take();
+        LIVE_AUDIO.lock().take();
+        if let Some(file_name) = CURRENT_FILE_NAME.lock().take() {
+            if let Ok(dir) = ensure_recordings_dir(app) {
+                let _ = std::fs::remove_file(dir.join(file_name));
+            }
         }
-    };
+        error::report(app, &e);
+        return;
+    }
+
+    log::info!("Recording started");
+    transcription::emit_state(app, TranscriptionState::Recording, None, None);
+    let s = crate::settings::load_settings(app);
+    if s.prewarm_model_on_record {
+        prewarm_engine(app);
+    }
+    if overlay::is_expanded(&s) {
+        live_preview::start(app);
+    }
+    if s.overlay_mode.as_str() == "recording" {
+        overlay::show_recording_overlay(app);
+    }
+}
+
+fn start_recording(app: &tauri::AppHandle) -> Result<(), MurmureError> {
+    let recordings_dir =
+        ensure_recordings_dir(app).map_err(|e| MurmureError::RecordingFile(format!("{:#}", e)))?;
     let file_name = generate_unique_wav_name();
     let file_path = recordings_dir.join(&file_name);
     *CURRENT_FILE_NAME.lock() = Some(file_name.clone());
 
     let host = cpal::default_host();
-    let device = match host.default_input_device() {
-        Some(d) => d,
-        None => {
-            eprintln!("No input device available");
-            return;
-        }
-    };
-    let config = match device.default_input_config() {
-        Ok(c) => c,
-        Err(e) => {
-            eprintln!("No input config available: {}", e);
-            return;
-        }
-    };
+    let s = crate::settings::load_settings(app);
+    let device = input_device(&host, s.microphone.as_deref()).ok_or(MurmureError::NoInputDevice)?;
+    let config = device
+        .default_input_config()
+        .map_err(|e| MurmureError::InputConfig(e.to_string()))?;
 
-    let file = match File::create(&file_path) {
-        Ok(f) => f,
-        Err(e) => {
-            eprintln!("Failed to create WAV file: {}", e);
-            return;
-        }
-    };
+    let file = File::create(&file_path).map_err(|e| MurmureError::RecordingFile(e.to_string()))?;
     let writer = BufWriter::new(file);
     let spec = WavSpec {
         channels: 1,
@@ -80,55 +141,79 @@ pub fn record_audio(app: &tauri::AppHandle) {
         bits_per_sample: 16,
         sample_format: hound::SampleFormat::Int,
     };
-    let wav_writer = match WavWriter::new(writer, spec) {
-        Ok(w) => w,
-        Err(e) => {
-            eprintln!("Failed to create WAV writer: {}", e);
-            return;
-        }
-    };
+    let wav_writer =
+        WavWriter::new(writer, spec).map_err(|e| MurmureError::RecordingFile(e.to_string()))?;
 
     let writer_arc = Arc::new(Mutex::new(Some(wav_writer)));
 
     *RECORDER.lock() = Some(writer_arc.clone());
 
+    let live = overlay::is_expanded(&s).then(|| {
+        let sample_rate = config.sample_rate().0;
+        let capacity = live_preview::PARTIAL_WINDOW_SECONDS * sample_rate as usize;
+        Arc::new(LiveAudio {
+            samples: Mutex::new(VecDeque::with_capacity(capacity)),
+            capacity,
+            sample_rate,
+        })
+    });
+    *LIVE_AUDIO.lock() = live.clone();
+
     let stream = match config.sample_format() {
-        cpal::SampleFormat::F32 => build_stream::<f32>(&device, &config, writer_arc, app.clone()),
-        cpal::SampleFormat::I16 => build_stream::<i16>(&device, &config, writer_arc, app.clone()),
-        cpal::SampleFormat::I32 => build_stream::<i32>(&device, &config, writer_arc, app.clone()),
-        _ => {
-            eprintln!("Unsupported sample format");
-            return;
+        cpal::SampleFormat::F32 => {
+            build_stream::<f32>(&device, &config, writer_arc, live, app.clone())
         }
-    };
-
-    match stream.play() {
-        Ok(_) => {}
-        Err(e) => {
-            eprintln!("Failed to start stream: {}", e);
-            return;
+        cpal::SampleFormat::I16 => {
+            build_stream::<i16>(&device, &config, writer_arc, live, app.clone())
+        }
+        cpal::SampleFormat::I32 => {
+            build_stream::<i32>(&device, &config, writer_arc, live, app.clone())
         }
+        format => return Err(MurmureError::UnsupportedSampleFormat(format.to_string())),
     }
+    .map_err(|e| MurmureError::Stream(e.to_string()))?;
+
+    stream
+        .play()
+        .map_err(|e| MurmureError::Stream(e.to_string()))?;
     *STREAM.lock() = Some(stream);
+    Ok(())
+}
 
-    println!("Recording started");
-    let s = crate::settings::load_settings(app);
-    if s.overlay_mode.as_str() == "recording" {
-        overlay::show_recording_overlay(app);
+/// The chosen microphone, or the default one if it is gone.
+fn input_device(host: &cpal::Host, name: Option<&str>) -> Option<cpal::Device> {
+    if let Some(name) = name {
+        let device = host
+            .input_devices()
+            .ok()
+            .and_then(|mut devices| devices.find(|device| device.name().is_ok_and(|n| n == name)));
+        if device.is_some() {
+            return device;
+        }
+        log::warn!("Microphone '{}' not found, using the default one", name);
     }
+    host.default_input_device()
+}
+
+pub fn list_microphones() -> Vec<String> {
+    cpal::default_host()
+        .input_devices()
+        .map(|devices| devices.filter_map(|device| device.name().ok()).collect())
+        .unwrap_or_default()
 }
 
 pub fn stop_recording(app: &tauri::AppHandle) -> Option<std::path::PathBuf> {
-    println!("Stopping audio recording...");
+    log::info!("Stopping audio recording...");
 
     if let Some(stream) = STREAM.lock().take() {
         drop(stream);
     }
+    LIVE_AUDIO.lock().take();
     if let Some(recorder_arc) = RECORDER.lock().take() {
         let mut recorder = recorder_arc.lock();
         if let Some(writer) = recorder.take() {
             if let Err(e) = writer.finalize() {
-                eprintln!("Failed to finalize WAV file: {}", e);
+                error::report(app, &MurmureError::RecordingFile(e.to_string()));
             }
         }
     }
@@ -138,73 +223,290 @@ pub fn stop_recording(app: &tauri::AppHandle) -> Option<std::path::PathBuf> {
             .map(|dir| dir.join(&file_name))
             .ok();
         if let Some(ref p) = path {
-            println!("Recording stopped and saved as {}", p.display());
-
-            match preload_engine(app) {
-                Ok(_) => match transcribe_audio(p.as_path()) {
-                    Ok(raw_text) => {
-                        println!("Raw transcription: {}", raw_text);
-                        match get_cc_rules_path(app) {
-                            Ok(cc_rules_path) => {
-                                let dictionary = app.state::<Dictionary>().get();
-                                let text = fix_transcription_with_dictionary(
-                                    raw_text,
-                                    dictionary,
-                                    cc_rules_path,
-                                );
-                                println!("Transcription fixed with dictionary: {}", text);
-                                if let Err(e) = history::add_transcription(app, text.clone()) {
-                                    eprintln!("Failed to save to history: {}", e);
-                                }
-                                if let Err(e) = write_transcription(app, &text) {
-                                    eprintln!("Failed to use clipboard: {}", e);
-                                }
-                            }
-                            Err(e) => {
-                                eprintln!("Failed to get CC rules path: {}", e);
-                            }
-                        }
-                    }
-                    Err(e) => eprintln!("Transcription failed: {}", e),
-                },
-                Err(e) => {
-                    eprintln!(
-                        "Cannot transcribe: Model not available. Please download a model first."
-                    );
-                    eprintln!("Error details: {}", e);
-                }
-            }
+            log::info!("Recording stopped and saved as {}", p.display());
+            *LAST_RECORDING.lock() = Some(p.clone());
+            transcription::enqueue(app, p.clone());
         } else {
-            println!("Recording stopped and saved as {}", file_name);
+            log::info!("Recording stopped and saved as {}", file_name);
         }
         // Emit a final zero level to let frontend reset visualizer
         let _ = app.emit("mic-level", 0.0f32);
         let s = crate::settings::load_settings(app);
         if s.overlay_mode.as_str() == "recording" {
-            overlay::hide_recording_overlay(app);
+            // Hidden by the transcription worker once the queue is done
+            if path.is_some() {
+                overlay::show_transcribing_overlay(app);
+            } else {
+                overlay::hide_recording_overlay(app);
+            }
         }
         return path;
     } else {
-        println!("Recording stopped");
+        log::info!("Recording stopped");
     }
     None
 }
 
-pub fn write_transcription(
+/// Stop recording without transcribing anything, discarding the audio.
+pub fn cancel_recording(app: &tauri::AppHandle) {
+    if !is_recording() {
+        return;
+    }
+    log::info!("Cancelling audio recording...");
+
+    if let Some(stream) = STREAM.lock().take() {
+        drop(stream);
+    }
+    LIVE_AUDIO.lock().take();
+    if let Some(recorder_arc) = RECORDER.lock().take() {
+        // Dropped without finalizing, the file is deleted below
+        recorder_arc.lock().take();
+    }
+    if let Some(file_name) = CURRENT_FILE_NAME.lock().take() {
+        if let Ok(dir) = ensure_recordings_dir(app) {
+            if let Err(e) = std::fs::remove_file(dir.join(&file_name)) {
+                log::error!("Failed to delete {}: {}", file_name, e);
+            }
+        }
+    }
+    log::info!("Recording cancelled");
+
+    let _ = app.emit("mic-level", 0.0f32);
+    let _ = app.emit("recording-cancelled", ());
+    overlay::show_cancelled_overlay(app);
+    let s = crate::settings::load_settings(app);
+    if s.overlay_mode.as_str() == "recording" {
+        // Once the cancelled state has been seen, back to a transcription still running
+        let app = app.clone();
+        std::thread::spawn(move || {
+            std::thread::sleep(CANCELLED_OVERLAY_DURATION);
+            if is_recording() {
+                return;
+            }
+            if app.state::<transcription::TranscriptionQueue>().pending() > 0 {
+                overlay::show_transcribing_overlay(&app);
+            } else {
+                overlay::hide_recording_overlay(&app);
+            }
+        });
+    }
+}
+
+pub fn is_recording() -> bool {
+    RECORDER.lock().is_some()
+}
+
+/// File name of the recording in progress.
+pub fn current_recording() -> Option<String> {
+    CURRENT_FILE_NAME.lock().clone()
+}
+
+/// Transcribe the last seconds of the recording in progress.
+/// Gives up rather than wait when the engine is busy with a queued recording.
+pub fn transcribe_live_tail(seconds: usize) -> Option<String> {
+    let live = LIVE_AUDIO.lock().clone()?;
+    let sample_rate = live.sample_rate as usize;
+    let samples = {
+        let samples = live.samples.lock();
+        let start = samples.len().saturating_sub(seconds * sample_rate);
+        samples.range(start..).copied().collect::<Vec<f32>>()
+    };
+    // Too short to make out any word
+    if samples.len() < sample_rate {
+        return None;
+    }
+    let samples = resample_linear(&samples, sample_rate, 16000);
+
+    let mut engine = ENGINE.try_lock()?;
+    let engine = engine.as_mut()?;
+    *ENGINE_LAST_USED.lock() = Instant::now();
+    match engine.transcribe_samples(samples, TimestampGranularity::Token) {
+        Ok(result) => Some(result.text),
+        Err(e) => {
+            log::warn!("Live transcription failed: {}", e);
+            None
+        }
+    }
+}
+
+/// Transcribe a recording, post-process it, save it to history and write it out.
+/// Runs on the transcription worker, see `transcription::enqueue`.
+pub fn transcribe_and_write(
     app: &tauri::AppHandle,
-    transcription: &str,
-) -> Result<(), anyhow::Error> {
-    if let Err(e) = clipboard::paste(transcription, app) {
-        eprintln!("Failed to paste text: {}", e);
+    path: &std::path::Path,
+) -> Result<String, MurmureError> {
+    preload_engine(app).map_err(|e| MurmureError::ModelNotAvailable(format!("{:#}", e)))?;
+    let result =
+        transcribe_audio(path).map_err(|e| MurmureError::Transcription(format!("{:#}", e)))?;
+    // Logs are saved to disk, so the dictated text itself only goes to debug
+    log::info!("Raw transcription: {} chars", result.text.chars().count());
+    log::debug!("Raw transcription: {}", result.text);
+
+    // Resolved once: the focused window is looked up for it
+    let (s, profile) = output_settings(app);
+    let detected = language::detect(&result.text);
+    log::info!("Detected language: {:?}", detected);
+    let lang = language::processing_language(&s, detected.as_deref());
+    let result = disfluency::clean_with_settings(&s, result, lang);
+    let confidence = result.confidence;
+    let skipped = confident_words(&result.segments, s.dictionary_skip_confidence);
+    let cc_rules_path =
+        get_cc_rules_path(app).map_err(|e| MurmureError::Dictionary(format!("{:#}", e)))?;
+    let dictionary = app.state::<Dictionary>().get();
+    let text =
+        fix_transcription_with_dictionary(result.text, dictionary, cc_rules_path, &skipped, lang);
+    log::debug!("Transcription fixed with dictionary: {}", text);
+    let text = itn::normalize_with_settings(&s, text, lang);
+
+    // Not worth losing the text over
+    if let Err(e) = history::add_transcription(app, text.clone(), confidence, detected.clone()) {
+        error::report(app, &MurmureError::History(format!("{:#}", e)));
+    }
+    write_with_profile(app, &text, &s, profile.as_ref())?;
+    Ok(text)
+}
+
+/// Filler removal, dictionary and ITN, for transcriptions that are not written out,
+/// like those of the HTTP API and folder imports.
+pub fn post_process(app: &tauri::AppHandle, result: TranscriptionResult) -> TranscriptionResult {
+    let s = crate::settings::load_settings(app);
+    let detected = language::detect(&result.text);
+    let lang = language::processing_language(&s, detected.as_deref());
+    let result = disfluency::clean_with_settings(&s, result, lang);
+    let text = match get_cc_rules_path(app) {
+        Ok(cc_rules_path) => {
+            let dictionary = app.state::<Dictionary>().get();
+            let skipped = confident_words(&result.segments, s.dictionary_skip_confidence);
+            fix_transcription_with_dictionary(
+                result.text,
+                dictionary,
+                cc_rules_path,
+                &skipped,
+                lang,
+            )
+        }
+        Err(_) => result.text,
+    };
+    TranscriptionResult {
+        text: itn::normalize_with_settings(&s, text, lang),
+        segments: result.segments,
+        confidence: result.confidence,
+        language: detected,
     }
+}
 
-    if let Err(e) = cleanup_recordings(app) {
-        eprintln!("Failed to cleanup recordings: {}", e);
-    } else {
-        println!("All recordings cleaned up");
+/// Transcribe WAV files in batches, with word-level segments. Results are in the
+/// order of `paths`, and `on_progress` is called with the number of files done.
+pub fn transcribe_files(
+    app: &tauri::AppHandle,
+    paths: &[PathBuf],
+    mut on_progress: impl FnMut(usize),
+) -> Result<Vec<Result<TranscriptionResult, String>>, MurmureError> {
+    preload_engine(app).map_err(|e| MurmureError::ModelNotAvailable(format!("{:#}", e)))?;
+
+    let mut results: Vec<Option<Result<TranscriptionResult, String>>> =
+        paths.iter().map(|_| None).collect();
+    let mut clips = Vec::new();
+    for (i, path) in paths.iter().enumerate() {
+        match read_wav_samples(path) {
+            Ok(samples) => clips.push((i, samples)),
+            Err(e) => results[i] = Some(Err(format!("Failed to read audio file: {:#}", e))),
+        }
+    }
+    // Clips of similar length go together, so that little padding is transcribed
+    clips.sort_by_key(|(_, samples)| samples.len());
+
+    let mut done = paths.len() - clips.len();
+    on_progress(done);
+    let mut clips = clips.into_iter().peekable();
+    while clips.peek().is_some() {
+        let mut batch: Vec<(usize, Vec<f32>)> = Vec::new();
+        while let Some((_, samples)) = clips.peek() {
+            // Sorted, so the next clip is the longest of the batch
+            let padded = (batch.len() + 1) * samples.len();
+            if !batch.is_empty() && (batch.len() == MAX_BATCH_CLIPS || padded > MAX_BATCH_SAMPLES) {
+                break;
+            }
+            batch.extend(clips.next());
+        }
+        let (indices, batch): (Vec<usize>, Vec<Vec<f32>>) = batch.into_iter().unzip();
+
+        let transcribed = match ENGINE.lock().as_mut() {
+            Some(engine) => engine
+                .transcribe_batch(batch, TimestampGranularity::Word)
+                .map_err(|e| format!("Transcription failed: {}", e)),
+            None => Err("Engine not loaded".to_string()),
+        };
+        *ENGINE_LAST_USED.lock() = Instant::now();
+        match transcribed {
+            Ok(transcribed) => {
+                for (&i, result) in indices.iter().zip(transcribed) {
+                    results[i] = Some(Ok(result));
+                }
+            }
+            Err(e) => {
+                log::error!("{}", e);
+                for &i in &indices {
+                    results[i] = Some(Err(e.clone()));
+                }
+            }
+        }
+        done += indices.len();
+        on_progress(done);
+    }
+
+    Ok(results
+        .into_iter()
+        .map(|result| result.unwrap_or_else(|| Err("No transcription result".to_string())))
+        .collect())
+}
+
+/// Queue the last recording for transcription again, e.g. after changing the dictionary.
+pub fn retranscribe_last(app: &tauri::AppHandle) {
+    let Some(path) = LAST_RECORDING.lock().clone().filter(|p| p.exists()) else {
+        log::warn!("No recording to transcribe again");
+        return;
+    };
+    log::info!("Transcribing {} again", path.display());
+    transcription::enqueue(app, path);
+}
+
+/// Settings with the active output profile applied, along with that profile.
+fn output_settings(app: &tauri::AppHandle) -> (AppSettings, Option<OutputProfile>) {
+    let s = crate::settings::load_settings(app);
+    match profiles::active_profile(&s) {
+        Some(profile) => (profile.apply_to_settings(&s), Some(profile)),
+        None => (s, None),
     }
+}
 
-    println!("Transcription written to clipboard {}", transcription);
+pub fn write_transcription(
+    app: &tauri::AppHandle,
+    transcription: &str,
+) -> Result<(), MurmureError> {
+    let (s, profile) = output_settings(app);
+    write_with_profile(app, transcription, &s, profile.as_ref())
+}
+
+/// Write out a transcription with settings from `output_settings`.
+fn write_with_profile(
+    app: &tauri::AppHandle,
+    transcription: &str,
+    s: &AppSettings,
+    profile: Option<&OutputProfile>,
+) -> Result<(), MurmureError> {
+    let text = match profile {
+        Some(profile) => profile.format_text(transcription),
+        None => transcription.to_string(),
+    };
+    let paste_keys = crate::shortcuts::parse_binding_keys(&s.paste_shortcut);
+    clipboard::write_text(&text, app, s.output_strategy, &paste_keys)
+        .map_err(MurmureError::Output)?;
+
+    log::info!(
+        "Transcription written out ({} chars)",
+        transcription.chars().count()
+    );
     Ok(())
 }
 
@@ -263,19 +565,118 @@ pub fn preload_engine(app: &tauri::AppHandle) -> Result<()> {
             .get_model_path()
             .map_err(|e| anyhow::anyhow!("Failed to get model path: {}", e))?;
 
-        let mut new_engine = ParakeetEngine::new();
+        let s = crate::settings::load_settings(app);
+        let engine_params = s.engine_params();
+        let mut params = match engine_params.precision.as_str() {
+            "fp32" => ParakeetModelParams::fp32(),
+            _ => ParakeetModelParams::int8(),
+        };
+        let onnx = &engine_params.onnx_runtime;
+        params.intra_threads = Some(onnx.intra_threads).filter(|&n| n > 0);
+        params.inter_threads = Some(onnx.inter_threads).filter(|&n| n > 0);
+        params.optimization_level = match onnx.optimization_level.as_str() {
+            "disable" => OptimizationLevel::Disable,
+            "basic" => OptimizationLevel::Basic,
+            "extended" => OptimizationLevel::Extended,
+            _ => OptimizationLevel::All,
+        };
+        if onnx.save_optimized_model {
+            params.optimized_model_dir = app
+                .path()
+                .app_cache_dir()
+                .ok()
+                .map(|dir| dir.join("optimized-models").join(&s.active_model));
+        }
+
+        let mut new_engine = create_engine(&s.engine, params)
+            .ok_or_else(|| anyhow::anyhow!("Unknown transcription engine '{}'", s.engine))?;
         new_engine
-            .load_model_with_params(&model_path, ParakeetModelParams::int8())
+            .load_model(&model_path)
             .map_err(|e| anyhow::anyhow!("Failed to load model: {}", e))?;
 
+        let info = LoadedModelInfo {
+            engine: s.engine.clone(),
+            path: model_path.to_string_lossy().to_string(),
+            precision: engine_params.precision,
+            onnx_files: new_engine.model_files(),
+        };
         *engine = Some(new_engine);
-        println!("Model loaded and cached in memory");
+        log::info!(
+            "Model loaded and cached in memory ({} {}): {}",
+            info.engine,
+            info.precision,
+            info.onnx_files.join(", ")
+        );
+        *LOADED_MODEL.lock() = Some(info.clone());
+        let _ = app.emit("model-loaded", info);
     }
+    // Callers transcribe right after, the idle unload must not sneak in between
+    *ENGINE_LAST_USED.lock() = Instant::now();
 
     Ok(())
 }
 
-pub fn transcribe_audio(audio_path: &std::path::Path) -> Result<String> {
+/// The model currently in memory, if any.
+pub fn loaded_model() -> Option<LoadedModelInfo> {
+    LOADED_MODEL.lock().clone()
+}
+
+/// Start loading the model in the background if it was unloaded, so that it is
+/// ready by the time the recording stops.
+pub fn prewarm_engine(app: &tauri::AppHandle) {
+    // Busy or already loaded
+    if !ENGINE.try_lock().is_some_and(|engine| engine.is_none()) {
+        return;
+    }
+    let app = app.clone();
+    std::thread::spawn(move || {
+        log::info!("Prewarming transcription engine");
+        if let Err(e) = preload_engine(&app) {
+            // Reported when the recording is transcribed
+            log::warn!("Failed to prewarm transcription engine: {:#}", e);
+        }
+    });
+}
+
+/// Time since the engine was last used, None when no model is loaded.
+pub fn engine_idle_time() -> Option<Duration> {
+    LOADED_MODEL
+        .lock()
+        .is_some()
+        .then(|| ENGINE_LAST_USED.lock().elapsed())
+}
+
+/// Free the model if it has not been used for `timeout`. Returns whether it was unloaded.
+/// Does nothing while the engine is busy.
+pub fn unload_engine_if_idle(timeout: Duration) -> bool {
+    let Some(mut engine) = ENGINE.try_lock() else {
+        return false;
+    };
+    if engine.is_none() || ENGINE_LAST_USED.lock().elapsed() < timeout {
+        return false;
+    }
+    if let Some(mut engine) = engine.take() {
+        engine.unload_model();
+    }
+    LOADED_MODEL.lock().take();
+    log::info!(
+        "Model unloaded after {} minutes unused",
+        timeout.as_secs() / 60
+    );
+    true
+}
+
+/// Drop the loaded model and load the active one, e.g. after switching models.
+pub fn reload_engine(app: &tauri::AppHandle) -> Result<()> {
+    if let Some(mut engine) = ENGINE.lock().take() {
+        engine.unload_model();
+    }
+    LOADED_MODEL.lock().take();
+    preload_engine(app)
+}
+
+/// Transcribe a WAV file, with word-level segments.
+pub fn transcribe_audio(audio_path: &std::path::Path) -> Result<TranscriptionResult> {
     let samples = read_wav_samples(audio_path)?;
 
     let mut engine = ENGINE.lock();
@@ -284,10 +685,11 @@ pub fn transcribe_audio(audio_path: &std::path::Path) -> Result<String> {
         .ok_or_else(|| anyhow::anyhow!("Engine not loaded"))?;
 
     let result = engine
-        .transcribe_samples(samples, None)
+        .transcribe_samples(samples, TimestampGranularity::Word)
         .map_err(|e| anyhow::anyhow!("Transcription failed: {}", e))?;
+    *ENGINE_LAST_USED.lock() = Instant::now();
 
-    Ok(result.text)
+    Ok(result)
 }
 
 fn ensure_recordings_dir(app: &tauri::AppHandle) -> Result<PathBuf> {
@@ -304,7 +706,9 @@ fn ensure_recordings_dir(app: &tauri::AppHandle) -> Result<PathBuf> {
     Ok(recordings)
 }
 
-fn cleanup_recordings(app: &tauri::AppHandle) -> Result<()> {
+/// Delete the recordings, except the last one and the one being recorded.
+/// Only called once no transcription is queued.
+pub fn cleanup_recordings(app: &tauri::AppHandle) -> Result<()> {
     let recordings_dir = ensure_recordings_dir(app)?;
 
     if !recordings_dir.exists() {
@@ -314,24 +718,32 @@ fn cleanup_recordings(app: &tauri::AppHandle) -> Result<()> {
     let entries =
         std::fs::read_dir(&recordings_dir).context("Failed to read recordings directory")?;
 
+    let last_recording = LAST_RECORDING.lock().clone();
+    let current_recording = CURRENT_FILE_NAME
+        .lock()
+        .clone()
+        .map(|name| recordings_dir.join(name));
     for entry in entries {
         if let Ok(entry) = entry {
-            if entry.path().is_file() {
+            let path = Some(entry.path());
+            if entry.path().is_file() && path != last_recording && path != current_recording {
                 if let Err(e) = std::fs::remove_file(entry.path()) {
-                    eprintln!("Failed to delete {}: {}", entry.path().display(), e);
+                    log::error!("Failed to delete {}: {}", entry.path().display(), e);
                 }
             }
         }
     }
+    log::info!("All recordings cleaned up");
 
     Ok(())
 }
 
+/// Millisecond precision, since a recording can start while the previous one is queued.
 fn generate_unique_wav_name() -> String {
     let ts = std::time::SystemTime::now()
         .duration_since(std::time::UNIX_EPOCH)
         .unwrap()
-        .as_secs();
+        .as_millis();
     format!("murmure-{}.wav", ts)
 }
 
@@ -339,8 +751,9 @@ fn build_stream<T>(
     device: &cpal::Device,
     config: &cpal::SupportedStreamConfig,
     writer: Arc<RecorderType>,
+    live: Option<Arc<LiveAudio>>,
     app: AppHandle,
-) -> cpal::Stream
+) -> Result<cpal::Stream, cpal::BuildStreamError>
 where
     T: cpal::Sample + cpal::SizedSample + Send + 'static,
     f32: cpal::FromSample<T>,
@@ -354,64 +767,70 @@ where
     let alpha: f32 = 0.35; // smoothing factor
     let mut last_emit = std::time::Instant::now();
 
-    device
-        .build_input_stream(
-            &config.clone().into(),
-            move |data: &[T], _: &cpal::InputCallbackInfo| {
-                let mut recorder = writer.lock();
-                if let Some(writer) = recorder.as_mut() {
-                    for frame in data.chunks_exact(channels) {
-                        let sample = if channels == 1 {
-                            frame[0].to_sample::<f32>()
-                        } else {
-                            frame.iter().map(|&s| s.to_sample::<f32>()).sum::<f32>()
-                                / channels as f32
-                        };
-
-                        // write to WAV
-                        let sample_i16 = (sample * i16::MAX as f32) as i16;
-                        if let Err(e) = writer.write_sample(sample_i16) {
-                            eprintln!("Error writing sample: {}", e);
-                        }
+    device.build_input_stream(
+        &config.clone().into(),
+        move |data: &[T], _: &cpal::InputCallbackInfo| {
+            let mut recorder = writer.lock();
+            let mut live_samples = live
+                .as_ref()
+                .map(|live| (live.samples.lock(), live.capacity));
+            if let Some(writer) = recorder.as_mut() {
+                for frame in data.chunks_exact(channels) {
+                    let sample = if channels == 1 {
+                        frame[0].to_sample::<f32>()
+                    } else {
+                        frame.iter().map(|&s| s.to_sample::<f32>()).sum::<f32>() / channels as f32
+                    };
 
-                        // accumulate for RMS
-                        acc_sum_squares += sample * sample;
-                        acc_count += 1;
+                    // write to WAV
+                    let sample_i16 = (sample * i16::MAX as f32) as i16;
+                    if let Err(e) = writer.write_sample(sample_i16) {
+                        log::error!("Error writing sample: {}", e);
+                    }
+                    if let Some((live_samples, capacity)) = live_samples.as_mut() {
+                        if live_samples.len() >= *capacity {
+                            live_samples.pop_front();
+                        }
+                        live_samples.push_back(sample);
                     }
+
+                    // accumulate for RMS
+                    acc_sum_squares += sample * sample;
+                    acc_count += 1;
                 }
+            }
 
-                // Throttle to ~30 FPS
-                if last_emit.elapsed() >= std::time::Duration::from_millis(33) {
-                    if acc_count > 0 {
-                        let rms = (acc_sum_squares / acc_count as f32).sqrt();
-                        // Normalize a bit and clamp
-                        let mut level = (rms * 1.5).min(1.0);
-                        // simple noise gate
-                        if level < 0.02 {
-                            level = 0.0;
-                        }
-                        // EMA smoothing
-                        ema_level = alpha * level + (1.0 - alpha) * ema_level;
-                        let _ = app.emit("mic-level", ema_level);
-                        // also forward to overlay window if present
-                        if let Some(overlay_window) = app.get_webview_window("recording_overlay") {
-                            let _ = overlay_window.emit("mic-level", ema_level);
-                        }
-                        acc_sum_squares = 0.0;
-                        acc_count = 0;
-                    } else {
-                        let _ = app.emit("mic-level", 0.0f32);
-                        if let Some(overlay_window) = app.get_webview_window("recording_overlay") {
-                            let _ = overlay_window.emit("mic-level", 0.0f32);
-                        }
+            // Throttle to ~30 FPS
+            if last_emit.elapsed() >= std::time::Duration::from_millis(33) {
+                if acc_count > 0 {
+                    let rms = (acc_sum_squares / acc_count as f32).sqrt();
+                    // Normalize a bit and clamp
+                    let mut level = (rms * 1.5).min(1.0);
+                    // simple noise gate
+                    if level < 0.02 {
+                        level = 0.0;
+                    }
+                    // EMA smoothing
+                    ema_level = alpha * level + (1.0 - alpha) * ema_level;
+                    let _ = app.emit("mic-level", ema_level);
+                    // also forward to overlay window if present
+                    if let Some(overlay_window) = app.get_webview_window("recording_overlay") {
+                        let _ = overlay_window.emit("mic-level", ema_level);
+                    }
+                    acc_sum_squares = 0.0;
+                    acc_count = 0;
+                } else {
+                    let _ = app.emit("mic-level", 0.0f32);
+                    if let Some(overlay_window) = app.get_webview_window("recording_overlay") {
+                        let _ = overlay_window.emit("mic-level", 0.0f32);
                     }
-                    last_emit = std::time::Instant::now();
                 }
-            },
-            |err| eprintln!("Stream error: {}", err),
-            None,
-        )
-        .expect("Failed to build input stream")
+                last_emit = std::time::Instant::now();
+            }
+        },
+        |err| log::error!("Stream error: {}", err),
+        None,
+    )
 }
 
 fn resample_linear(input: &[f32], src_hz: usize, dst_hz: usize) -> Vec<f32> {
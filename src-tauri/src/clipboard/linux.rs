@@ -0,0 +1,557 @@
+use std::time::{Duration, Instant};
+use x11rb::connection::{Connection, RequestConnection};
+use x11rb::protocol::xproto::{
+    Atom, AtomEnum, ChangeWindowAttributesAux, ConnectionExt, CreateWindowAux, EventMask, PropMode,
+    Property, SelectionNotifyEvent, SelectionRequestEvent, Window, WindowClass,
+    SELECTION_NOTIFY_EVENT,
+};
+use x11rb::protocol::Event;
+use x11rb::rust_connection::RustConnection;
+use x11rb::wrapper::ConnectionExt as _;
+use x11rb::{COPY_DEPTH_FROM_PARENT, CURRENT_TIME, NONE};
+
+x11rb::atom_manager! {
+    Atoms: AtomsCookie {
+        CLIPBOARD,
+        TARGETS,
+        MULTIPLE,
+        TIMESTAMP,
+        SAVE_TARGETS,
+        DELETE,
+        INCR,
+        UTF8_STRING,
+        TEXT,
+        TEXT_PLAIN: b"text/plain",
+        TEXT_PLAIN_UTF8: b"text/plain;charset=utf-8",
+        MURMURE_SELECTION,
+    }
+}
+
+/// Timeout for the clipboard owner to answer one conversion request.
+const READ_TIMEOUT: Duration = Duration::from_millis(500);
+/// Budget for saving the whole clipboard, formats left over when it runs out are dropped.
+const SNAPSHOT_TIMEOUT: Duration = Duration::from_millis(1000);
+/// How long the focused application gets to fetch the pasted text.
+const PASTE_TIMEOUT: Duration = Duration::from_millis(1500);
+/// Requests arriving this soon after the first one belong to the same paste.
+const PASTE_GRACE: Duration = Duration::from_millis(150);
+/// Upper bound for finishing incremental transfers of large data.
+const INCR_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// One clipboard format: the target it is offered as, and its data.
+struct Format {
+    target: Atom,
+    type_: Atom,
+    format: u8,
+    data: Vec<u8>,
+}
+
+/// A large format being sent in chunks (ICCCM INCR protocol).
+struct IncrTransfer {
+    requestor: Window,
+    property: Atom,
+    type_: Atom,
+    format: u8,
+    data: Vec<u8>,
+    offset: usize,
+}
+
+impl IncrTransfer {
+    /// The next chunk of data, empty once everything was sent, which ends the transfer.
+    fn next_chunk(&mut self, chunk_size: usize) -> &[u8] {
+        let start = self.offset;
+        self.offset = (start + chunk_size).min(self.data.len());
+        &self.data[start..self.offset]
+    }
+}
+
+enum PasteOutcome {
+    Fetched,
+    TimedOut,
+    /// Another application took the clipboard over in the meantime
+    Lost,
+}
+
+enum Served {
+    Nothing,
+    Data,
+    Lost,
+}
+
+/// X11 CLIPBOARD selection owned by a hidden window of our own, so that
+/// every format of the previous clipboard can be handed back after a paste.
+pub struct Clipboard {
+    conn: RustConnection,
+    window: Window,
+    atoms: Atoms,
+    transfers: Vec<IncrTransfer>,
+}
+
+impl Clipboard {
+    pub fn open() -> Option<Self> {
+        let (conn, screen_num) = x11rb::connect(None).ok()?;
+        let root = conn.setup().roots.get(screen_num)?.root;
+        let atoms = Atoms::new(&conn).ok()?.reply().ok()?;
+
+        let window = conn.generate_id().ok()?;
+        conn.create_window(
+            COPY_DEPTH_FROM_PARENT,
+            window,
+            root,
+            0,
+            0,
+            1,
+            1,
+            0,
+            WindowClass::INPUT_OUTPUT,
+            0,
+            &CreateWindowAux::new().event_mask(EventMask::PROPERTY_CHANGE),
+        )
+        .ok()?;
+        conn.flush().ok()?;
+
+        Some(Self {
+            conn,
+            window,
+            atoms,
+            transfers: Vec::new(),
+        })
+    }
+
+    /// Put the text on the clipboard, run `paste` and restore the previous
+    /// clipboard, with all of its formats, once the focused application has
+    /// fetched the text. Restoring runs in the background.
+    pub fn paste(
+        mut self,
+        text: &str,
+        paste: impl FnOnce() -> Result<(), String>,
+    ) -> Result<(), String> {
+        let previous = self.snapshot();
+        let formats = self.text_formats(text);
+        self.take_ownership()?;
+
+        std::thread::spawn(move || {
+            match self.serve_paste(&formats) {
+                PasteOutcome::Lost => return,
+                PasteOutcome::TimedOut => {
+                    log::warn!("Pasted text was not fetched in time, restoring clipboard anyway")
+                }
+                PasteOutcome::Fetched => {}
+            }
+            self.restore(&previous);
+        });
+
+        paste()
+    }
+
+    /// Every format the current clipboard owner offers.
+    fn snapshot(&self) -> Vec<Format> {
+        let owner = self
+            .conn
+            .get_selection_owner(self.atoms.CLIPBOARD)
+            .ok()
+            .and_then(|cookie| cookie.reply().ok())
+            .map(|reply| reply.owner)
+            .unwrap_or(NONE);
+        if owner == NONE {
+            return Vec::new();
+        }
+
+        let deadline = Instant::now() + SNAPSHOT_TIMEOUT;
+        let Some(targets) = self.read(self.atoms.TARGETS, deadline) else {
+            return Vec::new();
+        };
+        let mut targets: Vec<Atom> = targets
+            .data
+            .chunks_exact(4)
+            .map(|c| u32::from_ne_bytes([c[0], c[1], c[2], c[3]]))
+            .filter(|&target| !self.is_meta_target(target))
+            .collect();
+        targets.dedup();
+
+        let mut formats = Vec::new();
+        for (i, &target) in targets.iter().enumerate() {
+            if Instant::now() >= deadline {
+                log::warn!(
+                    "Clipboard too slow to save, {} format(s) skipped",
+                    targets.len() - i
+                );
+                break;
+            }
+            formats.extend(self.read(target, deadline));
+        }
+        log::info!("Saved {} clipboard format(s)", formats.len());
+        formats
+    }
+
+    fn is_meta_target(&self, target: Atom) -> bool {
+        let a = &self.atoms;
+        [
+            a.TARGETS,
+            a.MULTIPLE,
+            a.TIMESTAMP,
+            a.SAVE_TARGETS,
+            a.DELETE,
+            a.INCR,
+        ]
+        .contains(&target)
+    }
+
+    /// Ask the clipboard owner to convert its content to `target`, giving up at `deadline`.
+    fn read(&self, target: Atom, deadline: Instant) -> Option<Format> {
+        let timeout = || deadline.min(Instant::now() + READ_TIMEOUT);
+        let property = self.atoms.MURMURE_SELECTION;
+        self.conn
+            .convert_selection(
+                self.window,
+                self.atoms.CLIPBOARD,
+                target,
+                property,
+                CURRENT_TIME,
+            )
+            .ok()?;
+        self.conn.flush().ok()?;
+
+        let clipboard = self.atoms.CLIPBOARD;
+        let event = self.wait_for(timeout(), |event| {
+            matches!(event, Event::SelectionNotify(e) if e.selection == clipboard && e.target == target)
+        })?;
+        if matches!(event, Event::SelectionNotify(e) if e.property == NONE) {
+            return None;
+        }
+
+        let reply = self.take_property()?;
+        if reply.type_ != self.atoms.INCR {
+            return Some(Format {
+                target,
+                type_: reply.type_,
+                format: reply.format,
+                data: reply.value,
+            });
+        }
+
+        // Large data: the owner sends chunks each time we delete the property,
+        // and an empty chunk at the end
+        let mut format = Format {
+            target,
+            type_: NONE,
+            format: 8,
+            data: Vec::new(),
+        };
+        loop {
+            let window = self.window;
+            self.wait_for(timeout(), |event| {
+                matches!(event, Event::PropertyNotify(e)
+                    if e.window == window && e.atom == property && e.state == Property::NEW_VALUE)
+            })?;
+            let chunk = self.take_property()?;
+            if chunk.value.is_empty() {
+                return Some(format);
+            }
+            format.type_ = chunk.type_;
+            format.format = chunk.format;
+            format.data.extend_from_slice(&chunk.value);
+        }
+    }
+
+    /// Read and delete our transfer property.
+    fn take_property(&self) -> Option<x11rb::protocol::xproto::GetPropertyReply> {
+        self.conn
+            .get_property(
+                true,
+                self.window,
+                self.atoms.MURMURE_SELECTION,
+                AtomEnum::ANY,
+                0,
+                u32::MAX / 4,
+            )
+            .ok()?
+            .reply()
+            .ok()
+    }
+
+    fn wait_for(&self, deadline: Instant, mut wanted: impl FnMut(&Event) -> bool) -> Option<Event> {
+        loop {
+            match self.conn.poll_for_event().ok()? {
+                Some(event) if wanted(&event) => return Some(event),
+                Some(_) => continue,
+                None if Instant::now() >= deadline => return None,
+                None => std::thread::sleep(Duration::from_millis(2)),
+            }
+        }
+    }
+
+    fn text_formats(&self, text: &str) -> Vec<Format> {
+        let utf8 = |target| Format {
+            target,
+            type_: self.atoms.UTF8_STRING,
+            format: 8,
+            data: text.as_bytes().to_vec(),
+        };
+        let latin1 = text
+            .chars()
+            .map(|c| u8::try_from(u32::from(c)).unwrap_or(b'?'))
+            .collect();
+
+        vec![
+            utf8(self.atoms.UTF8_STRING),
+            utf8(self.atoms.TEXT_PLAIN_UTF8),
+            utf8(self.atoms.TEXT),
+            utf8(self.atoms.TEXT_PLAIN),
+            Format {
+                target: AtomEnum::STRING.into(),
+                type_: AtomEnum::STRING.into(),
+                format: 8,
+                data: latin1,
+            },
+        ]
+    }
+
+    fn take_ownership(&self) -> Result<(), String> {
+        self.conn
+            .set_selection_owner(self.window, self.atoms.CLIPBOARD, CURRENT_TIME)
+            .map_err(|e| format!("Failed to own clipboard: {}", e))?;
+        let owner = self
+            .conn
+            .get_selection_owner(self.atoms.CLIPBOARD)
+            .map_err(|e| format!("Failed to own clipboard: {}", e))?
+            .reply()
+            .map_err(|e| format!("Failed to own clipboard: {}", e))?
+            .owner;
+        if owner != self.window {
+            return Err("Failed to own clipboard".to_string());
+        }
+        Ok(())
+    }
+
+    /// Serve the pasted text until the focused application has fetched it.
+    fn serve_paste(&mut self, formats: &[Format]) -> PasteOutcome {
+        let start = Instant::now();
+        let mut last_request: Option<Instant> = None;
+        loop {
+            match last_request {
+                Some(at)
+                    if at.elapsed() >= PASTE_GRACE
+                        && (self.transfers.is_empty() || start.elapsed() >= INCR_TIMEOUT) =>
+                {
+                    return PasteOutcome::Fetched
+                }
+                None if start.elapsed() >= PASTE_TIMEOUT => return PasteOutcome::TimedOut,
+                _ => {}
+            }
+
+            match self.conn.poll_for_event() {
+                Ok(Some(event)) => match self.handle_event(event, formats) {
+                    Served::Data => last_request = Some(Instant::now()),
+                    Served::Lost => return PasteOutcome::Lost,
+                    Served::Nothing => {}
+                },
+                Ok(None) => std::thread::sleep(Duration::from_millis(2)),
+                Err(_) => return PasteOutcome::Lost,
+            }
+        }
+    }
+
+    /// Hand the saved formats back, serving them until another application
+    /// takes the clipboard over.
+    fn restore(&mut self, previous: &[Format]) {
+        if previous.is_empty() {
+            let _ = self
+                .conn
+                .set_selection_owner(NONE, self.atoms.CLIPBOARD, CURRENT_TIME);
+            let _ = self.conn.flush();
+            return;
+        }
+        if self.take_ownership().is_err() {
+            return;
+        }
+        while let Ok(event) = self.conn.wait_for_event() {
+            if let Served::Lost = self.handle_event(event, previous) {
+                return;
+            }
+        }
+    }
+
+    fn handle_event(&mut self, event: Event, formats: &[Format]) -> Served {
+        match event {
+            Event::SelectionRequest(request) if request.selection == self.atoms.CLIPBOARD => {
+                self.answer(&request, formats)
+            }
+            Event::SelectionClear(e) if e.selection == self.atoms.CLIPBOARD => Served::Lost,
+            Event::PropertyNotify(e) if e.state == Property::DELETE => {
+                self.continue_transfer(e.window, e.atom);
+                Served::Nothing
+            }
+            _ => Served::Nothing,
+        }
+    }
+
+    fn answer(&mut self, request: &SelectionRequestEvent, formats: &[Format]) -> Served {
+        // Obsolete clients leave the property unset and expect the target name
+        let property = if request.property == NONE {
+            request.target
+        } else {
+            request.property
+        };
+
+        let (converted, served) = if request.target == self.atoms.TARGETS {
+            let mut targets = vec![self.atoms.TARGETS];
+            targets.extend(formats.iter().map(|f| f.target));
+            let converted = self
+                .conn
+                .change_property32(
+                    PropMode::REPLACE,
+                    request.requestor,
+                    property,
+                    AtomEnum::ATOM,
+                    &targets,
+                )
+                .is_ok();
+            (converted, Served::Nothing)
+        } else if let Some(format) = formats.iter().find(|f| f.target == request.target) {
+            let converted = self.send_format(request.requestor, property, format);
+            (converted, Served::Data)
+        } else {
+            (false, Served::Nothing)
+        };
+
+        let notify = SelectionNotifyEvent {
+            response_type: SELECTION_NOTIFY_EVENT,
+            sequence: 0,
+            time: request.time,
+            requestor: request.requestor,
+            selection: request.selection,
+            target: request.target,
+            property: if converted { property } else { NONE },
+        };
+        let _ = self
+            .conn
+            .send_event(false, request.requestor, EventMask::NO_EVENT, notify);
+        let _ = self.conn.flush();
+        served
+    }
+
+    fn send_format(&mut self, requestor: Window, property: Atom, format: &Format) -> bool {
+        if format.data.len() <= self.chunk_size() {
+            return self
+                .conn
+                .change_property(
+                    PropMode::REPLACE,
+                    requestor,
+                    property,
+                    format.type_,
+                    format.format,
+                    element_count(format.data.len(), format.format),
+                    &format.data,
+                )
+                .is_ok();
+        }
+
+        // Too large for a single request: announce an incremental transfer
+        // and send a chunk each time the requestor deletes the property
+        let announced = self
+            .conn
+            .change_window_attributes(
+                requestor,
+                &ChangeWindowAttributesAux::new().event_mask(EventMask::PROPERTY_CHANGE),
+            )
+            .is_ok()
+            && self
+                .conn
+                .change_property32(
+                    PropMode::REPLACE,
+                    requestor,
+                    property,
+                    self.atoms.INCR,
+                    &[format.data.len() as u32],
+                )
+                .is_ok();
+        if announced {
+            self.transfers.push(IncrTransfer {
+                requestor,
+                property,
+                type_: format.type_,
+                format: format.format,
+                data: format.data.clone(),
+                offset: 0,
+            });
+        }
+        announced
+    }
+
+    fn continue_transfer(&mut self, requestor: Window, property: Atom) {
+        let Some(index) = self
+            .transfers
+            .iter()
+            .position(|t| t.requestor == requestor && t.property == property)
+        else {
+            return;
+        };
+        let chunk_size = self.chunk_size();
+        let transfer = &mut self.transfers[index];
+        let (type_, format) = (transfer.type_, transfer.format);
+        let chunk = transfer.next_chunk(chunk_size);
+        let _ = self.conn.change_property(
+            PropMode::REPLACE,
+            requestor,
+            property,
+            type_,
+            format,
+            element_count(chunk.len(), format),
+            chunk,
+        );
+        let _ = self.conn.flush();
+
+        // The empty chunk written after the last one ends the transfer
+        if chunk.is_empty() {
+            self.transfers.remove(index);
+        }
+    }
+
+    fn chunk_size(&self) -> usize {
+        let max = self.conn.maximum_request_bytes().saturating_sub(64);
+        (max.min(256 * 1024) / 4) * 4
+    }
+}
+
+impl Drop for Clipboard {
+    fn drop(&mut self) {
+        let _ = self.conn.destroy_window(self.window);
+        let _ = self.conn.flush();
+    }
+}
+
+fn element_count(len: usize, format: u8) -> u32 {
+    (len / usize::from(format.max(8) / 8)) as u32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn incr_transfer_ends_with_an_empty_chunk() {
+        let mut transfer = IncrTransfer {
+            requestor: 1,
+            property: 2,
+            type_: 3,
+            format: 8,
+            data: (0..10).collect(),
+            offset: 0,
+        };
+        assert_eq!(transfer.next_chunk(4), [0, 1, 2, 3]);
+        assert_eq!(transfer.next_chunk(4), [4, 5, 6, 7]);
+        assert_eq!(transfer.next_chunk(4), [8, 9]);
+        assert!(transfer.next_chunk(4).is_empty());
+        assert!(transfer.next_chunk(4).is_empty());
+    }
+
+    #[test]
+    fn element_count_follows_the_format() {
+        assert_eq!(element_count(12, 8), 12);
+        assert_eq!(element_count(12, 16), 6);
+        assert_eq!(element_count(12, 32), 3);
+        // Format 0 is not valid X11 data, counted as bytes
+        assert_eq!(element_count(12, 0), 12);
+    }
+}
@@ -0,0 +1,138 @@
+#[cfg(not(target_os = "linux"))]
+use enigo::Key;
+use enigo::{Enigo, Keyboard, Settings};
+use serde::{Deserialize, Serialize};
+use tauri_plugin_clipboard_manager::ClipboardExt;
+
+#[cfg(target_os = "linux")]
+mod linux;
+#[cfg(target_os = "windows")]
+mod windows;
+
+#[cfg(target_os = "linux")]
+use linux as native;
+#[cfg(target_os = "windows")]
+use windows as native;
+
+/// How the text reaches the focused application.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum OutputStrategy {
+    /// Clipboard and paste chord, the previous clipboard is restored afterwards
+    #[default]
+    Paste,
+    /// Unicode keystrokes, leaving the clipboard untouched. Not available on Wayland
+    Type,
+    /// Copy only
+    Clipboard,
+}
+
+/// Hand the text to the focused application using the chosen output strategy.
+pub fn write_text(
+    text: &str,
+    app_handle: &tauri::AppHandle,
+    strategy: OutputStrategy,
+    paste_keys: &[i32],
+) -> Result<(), String> {
+    match strategy {
+        OutputStrategy::Type => type_text(text),
+        OutputStrategy::Clipboard => app_handle
+            .clipboard()
+            .write_text(text)
+            .map_err(|e| format!("Failed to write to clipboard: {}", e)),
+        OutputStrategy::Paste => paste(text, app_handle, paste_keys),
+    }
+}
+
+pub fn paste(text: &str, app_handle: &tauri::AppHandle, paste_keys: &[i32]) -> Result<(), String> {
+    #[cfg(any(target_os = "linux", target_os = "windows"))]
+    if let Some(clipboard) = native::Clipboard::open() {
+        return clipboard.paste(text, || send_paste(paste_keys));
+    }
+
+    // Text-only fallback when the native clipboard is unavailable
+    let clipboard = app_handle.clipboard();
+    let clipboard_content = clipboard.read_text().unwrap_or_default();
+    clipboard
+        .write_text(text)
+        .map_err(|e| format!("Failed to write to clipboard: {}", e))?;
+
+    std::thread::sleep(std::time::Duration::from_millis(100));
+    send_paste(paste_keys)?;
+    std::thread::sleep(std::time::Duration::from_millis(300));
+
+    clipboard
+        .write_text(&clipboard_content)
+        .map_err(|e| format!("Failed to restore clipboard: {}", e))?;
+
+    Ok(())
+}
+
+/// Type the text as Unicode keystrokes, leaving the clipboard untouched.
+fn type_text(text: &str) -> Result<(), String> {
+    // Enigo types through the X server, which Wayland windows do not listen to,
+    // and uinput only sends key codes of the keyboard layout, not arbitrary text
+    #[cfg(target_os = "linux")]
+    if crate::shortcuts::is_wayland_session() {
+        return Err(
+            "Typing is not available on Wayland, use the paste or clipboard output instead"
+                .to_string(),
+        );
+    }
+
+    let mut enigo = Enigo::new(&Settings::default())
+        .map_err(|e| format!("Failed to initialize Enigo: {}", e))?;
+
+    enigo
+        .text(text)
+        .map_err(|e| format!("Failed to type text: {}", e))
+}
+
+/// Press the paste chord, given as virtual key codes (e.g. ctrl+v, ctrl+shift+v).
+fn send_paste(keys: &[i32]) -> Result<(), String> {
+    // uinput on Wayland, XTest on X11
+    #[cfg(target_os = "linux")]
+    {
+        return crate::shortcuts::send_keys(keys);
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    {
+        // Virtual key codes are the Windows ones, macOS keeps its fixed Cmd+V,
+        // which is why the paste shortcut cannot be changed there
+        #[cfg(target_os = "macos")]
+        let keys: Vec<Key> = {
+            // ctrl+v
+            if keys != [0x11, 0x56] {
+                log::warn!("Paste shortcut ignored on macOS, pasting with Cmd+V");
+            }
+            vec![Key::Meta, Key::Other(9)]
+        };
+        #[cfg(target_os = "windows")]
+        let keys: Vec<Key> = keys.iter().map(|&vk| Key::Other(vk as u32)).collect();
+
+        if keys.is_empty() {
+            return Err("Invalid paste shortcut".to_string());
+        }
+
+        let mut enigo = Enigo::new(&Settings::default())
+            .map_err(|e| format!("Failed to initialize Enigo: {}", e))?;
+
+        for (i, key) in keys.iter().enumerate() {
+            enigo
+                .key(*key, enigo::Direction::Press)
+                .map_err(|e| format!("Failed to press {:?}: {}", key, e))?;
+            if i == 0 {
+                std::thread::sleep(std::time::Duration::from_millis(20));
+            }
+        }
+
+        for key in keys.iter().rev() {
+            enigo
+                .key(*key, enigo::Direction::Release)
+                .map_err(|e| format!("Failed to release {:?}: {}", key, e))?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,197 @@
+use std::time::Duration;
+use windows_sys::Win32::Foundation::{GlobalFree, HANDLE, HGLOBAL};
+use windows_sys::Win32::System::DataExchange::{
+    CloseClipboard, EmptyClipboard, EnumClipboardFormats, GetClipboardData, OpenClipboard,
+    SetClipboardData,
+};
+use windows_sys::Win32::System::Memory::{
+    GlobalAlloc, GlobalLock, GlobalSize, GlobalUnlock, GMEM_MOVEABLE,
+};
+use windows_sys::Win32::UI::WindowsAndMessaging::{
+    GetForegroundWindow, SendMessageTimeoutW, SMTO_ABORTIFHUNG, SMTO_BLOCK, WM_NULL,
+};
+
+const CF_UNICODETEXT: u32 = 13;
+
+/// Formats whose handle is not a block of global memory (bitmaps, metafiles,
+/// palettes, owner-display and private formats). Windows synthesizes the
+/// common ones from formats that we do save, such as CF_BITMAP from CF_DIB.
+fn is_memory_format(format: u32) -> bool {
+    !matches!(
+        format,
+        2 | 3 | 9 | 14 | 0x80 | 0x82 | 0x83 | 0x8E | 0x200..=0x3FF
+    )
+}
+
+/// The Windows clipboard, saved and restored with every memory-backed format.
+pub struct Clipboard;
+
+impl Clipboard {
+    pub fn open() -> Option<Self> {
+        Some(Self)
+    }
+
+    /// Put the text on the clipboard, run `paste` and restore the previous
+    /// clipboard with all of its formats once the focused window has handled
+    /// the paste keystrokes.
+    pub fn paste(
+        self,
+        text: &str,
+        paste: impl FnOnce() -> Result<(), String>,
+    ) -> Result<(), String> {
+        let previous = snapshot()?;
+
+        replace(&[(CF_UNICODETEXT, unicode_text(text))])?;
+
+        let result = paste();
+        wait_for_foreground_idle();
+
+        replace(&previous).map_err(|e| format!("Failed to restore clipboard: {}", e))?;
+        result
+    }
+}
+
+/// CF_UNICODETEXT data: null-terminated UTF-16.
+fn unicode_text(text: &str) -> Vec<u8> {
+    text.encode_utf16()
+        .chain(std::iter::once(0))
+        .flat_map(|unit| unit.to_ne_bytes())
+        .collect()
+}
+
+/// Opens the clipboard for the duration of the guard's lifetime.
+struct OpenGuard;
+
+impl OpenGuard {
+    fn open() -> Result<Self, String> {
+        // Another application may briefly hold the clipboard
+        for _ in 0..10 {
+            if unsafe { OpenClipboard(0) } != 0 {
+                return Ok(Self);
+            }
+            std::thread::sleep(Duration::from_millis(10));
+        }
+        Err("Failed to open clipboard".to_string())
+    }
+}
+
+impl Drop for OpenGuard {
+    fn drop(&mut self) {
+        unsafe { CloseClipboard() };
+    }
+}
+
+fn snapshot() -> Result<Vec<(u32, Vec<u8>)>, String> {
+    let _guard = OpenGuard::open()?;
+    let mut formats = Vec::new();
+    let mut format = 0;
+    loop {
+        format = unsafe { EnumClipboardFormats(format) };
+        if format == 0 {
+            break;
+        }
+        if !is_memory_format(format) {
+            continue;
+        }
+        let handle = unsafe { GetClipboardData(format) } as HGLOBAL;
+        if handle.is_null() {
+            continue;
+        }
+        unsafe {
+            let size = GlobalSize(handle);
+            let data = GlobalLock(handle) as *const u8;
+            if data.is_null() {
+                continue;
+            }
+            formats.push((format, std::slice::from_raw_parts(data, size).to_vec()));
+            GlobalUnlock(handle);
+        }
+    }
+    log::info!("Saved {} clipboard format(s)", formats.len());
+    Ok(formats)
+}
+
+fn replace(formats: &[(u32, Vec<u8>)]) -> Result<(), String> {
+    let _guard = OpenGuard::open()?;
+    if unsafe { EmptyClipboard() } == 0 {
+        return Err("Failed to empty clipboard".to_string());
+    }
+    for (format, data) in formats {
+        unsafe {
+            let handle = GlobalAlloc(GMEM_MOVEABLE, data.len().max(1));
+            if handle.is_null() {
+                return Err("Failed to allocate clipboard memory".to_string());
+            }
+            let dest = GlobalLock(handle) as *mut u8;
+            if dest.is_null() {
+                GlobalFree(handle);
+                return Err("Failed to allocate clipboard memory".to_string());
+            }
+            std::ptr::copy_nonoverlapping(data.as_ptr(), dest, data.len());
+            GlobalUnlock(handle);
+
+            // The system owns the memory once it is set
+            if SetClipboardData(*format, handle as HANDLE) == 0 {
+                GlobalFree(handle);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Wait until the foreground window has worked through its input, so the
+/// clipboard is restored right after the paste instead of after a fixed delay.
+/// A hung window gives up after the timeout.
+fn wait_for_foreground_idle() {
+    std::thread::sleep(Duration::from_millis(30));
+    let hwnd = unsafe { GetForegroundWindow() };
+    if hwnd == 0 {
+        std::thread::sleep(Duration::from_millis(100));
+        return;
+    }
+    // Twice: the first round trip may be handled before the queued keystrokes
+    for _ in 0..2 {
+        let mut result = 0usize;
+        unsafe {
+            SendMessageTimeoutW(
+                hwnd,
+                WM_NULL,
+                0,
+                0,
+                SMTO_ABORTIFHUNG | SMTO_BLOCK,
+                1000,
+                &mut result,
+            )
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn handle_formats_are_not_saved() {
+        // CF_TEXT, CF_UNICODETEXT, CF_DIB, CF_HDROP
+        for format in [1, 13, 8, 15] {
+            assert!(is_memory_format(format), "{format}");
+        }
+        // CF_BITMAP, CF_METAFILEPICT, CF_PALETTE, CF_ENHMETAFILE, CF_OWNERDISPLAY,
+        // CF_DSPBITMAP, CF_DSPENHMETAFILE, private and GDI object formats
+        for format in [2, 3, 9, 14, 0x80, 0x82, 0x8E, 0x200, 0x2FF, 0x300, 0x3FF] {
+            assert!(!is_memory_format(format), "{format}");
+        }
+        // Registered formats such as "HTML Format"
+        assert!(is_memory_format(0xC000));
+    }
+
+    #[test]
+    fn unicode_text_is_null_terminated_utf16() {
+        let expected: Vec<u8> = [0x68, 0xE9, 0xD83D, 0xDE00, 0]
+            .iter()
+            .flat_map(|unit: &u16| unit.to_ne_bytes())
+            .collect();
+        assert_eq!(unicode_text("hé😀"), expected);
+        assert_eq!(unicode_text(""), [0, 0]);
+    }
+}
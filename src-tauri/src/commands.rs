@@ -1,13 +1,19 @@
+use crate::clipboard::OutputStrategy;
 use crate::dictionary::Dictionary;
+use crate::disfluency::FillerWords;
 use crate::history::{self, HistoryEntry};
-use crate::model::Model;
+use crate::model::{fetch_manifest, InstalledModel, Model, ModelInfo};
+use crate::profiles::OutputProfile;
 use crate::settings;
 use crate::shortcuts::{
-    keys_to_string, parse_binding_keys, LastTranscriptShortcutKeys, RecordShortcutKeys,
+    bindings_overlap, is_keyboard_key, keys_to_string, parse_binding_keys, validate_binding,
+    CustomShortcuts, LastTranscriptShortcutKeys, MatchMode, MaxRecordingDuration, RecordMode,
+    RecordShortcutKeys, RecordShortcutMode, ShortcutAction, ShortcutBinding, ShortcutMatchMode,
     TranscriptionSuspended,
 };
+use std::collections::HashMap;
 use std::sync::Arc;
-use tauri::{AppHandle, Manager, State};
+use tauri::{AppHandle, Emitter, Manager, State};
 use crate::http_api::HttpApiState;
 use crate::http_api::spawn_http_api_thread;
 
@@ -23,6 +29,258 @@ pub fn get_model_path(model: State<Arc<Model>>) -> Result<String, String> {
     Ok(path.to_string_lossy().to_string())
 }
 
+#[tauri::command]
+pub fn get_installed_models(model: State<Arc<Model>>) -> Result<Vec<InstalledModel>, String> {
+    model.installed_models().map_err(|e| format!("{:#}", e))
+}
+
+#[tauri::command]
+pub async fn get_available_models(app: AppHandle) -> Result<Vec<ModelInfo>, String> {
+    let s = settings::load_settings(&app);
+    fetch_manifest(&s.model_source)
+        .await
+        .map_err(|e| format!("{:#}", e))
+}
+
+/// Progress is reported through "model-download-progress" events.
+#[tauri::command]
+pub async fn download_model(app: AppHandle, name: String) -> Result<(), String> {
+    let s = settings::load_settings(&app);
+    let info = fetch_manifest(&s.model_source)
+        .await
+        .map_err(|e| format!("{:#}", e))?
+        .into_iter()
+        .find(|m| m.name == name)
+        .ok_or_else(|| format!("Model '{}' is not offered by the download source", name))?;
+    let model = app.state::<Arc<Model>>().inner().clone();
+    crate::model::download_model(&app, &model, &info, &s.model_source)
+        .await
+        .map_err(|e| format!("{:#}", e))?;
+    let _ = app.emit("models-updated", ());
+    Ok(())
+}
+
+#[tauri::command]
+pub fn delete_model(app: AppHandle, model: State<Arc<Model>>, name: String) -> Result<(), String> {
+    model.delete_model(&name).map_err(|e| format!("{:#}", e))?;
+    let _ = app.emit("models-updated", ());
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_active_model(app: AppHandle) -> Result<String, String> {
+    let s = settings::load_settings(&app);
+    Ok(s.active_model)
+}
+
+/// Switch models without restarting, going back to the previous one if the new one fails to load.
+#[tauri::command]
+pub async fn set_active_model(app: AppHandle, name: String) -> Result<(), String> {
+    let model = app.state::<Arc<Model>>().inner().clone();
+    model.model_path(&name).map_err(|e| format!("{:#}", e))?;
+
+    let mut s = settings::load_settings(&app);
+    let previous = std::mem::replace(&mut s.active_model, name.clone());
+    settings::save_settings(&app, &s)?;
+    reload_engine_or_revert(&app, move |s| s.active_model = previous)
+        .await
+        .map_err(|e| format!("Failed to load model '{}': {}", name, e))?;
+
+    log::info!("Active model switched to '{}'", name);
+    let _ = app.emit("model-changed", name);
+    Ok(())
+}
+
+/// Reload the engine after a model setting changed, undoing the change if the model fails to load.
+async fn reload_engine_or_revert(
+    app: &AppHandle,
+    revert: impl FnOnce(&mut settings::AppSettings) + Send,
+) -> Result<(), String> {
+    let app_handle = app.clone();
+    let loaded =
+        tauri::async_runtime::spawn_blocking(move || crate::audio::reload_engine(&app_handle))
+            .await
+            .map_err(|e| e.to_string())?;
+    let Err(e) = loaded else {
+        return Ok(());
+    };
+
+    let mut s = settings::load_settings(app);
+    revert(&mut s);
+    settings::save_settings(app, &s)?;
+    let app_handle = app.clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        if let Err(e) = crate::audio::reload_engine(&app_handle) {
+            log::error!("Failed to reload the previous model: {:#}", e);
+        }
+    });
+    Err(format!("{:#}", e))
+}
+
+#[tauri::command]
+pub fn get_engines() -> Result<Vec<String>, String> {
+    Ok(crate::engine::ENGINES
+        .iter()
+        .map(|e| e.to_string())
+        .collect())
+}
+
+#[tauri::command]
+pub fn get_engine(app: AppHandle) -> Result<String, String> {
+    let s = settings::load_settings(&app);
+    Ok(s.engine)
+}
+
+/// The model is loaded again with the new engine, which must support it.
+#[tauri::command]
+pub async fn set_engine(app: AppHandle, engine: String) -> Result<(), String> {
+    if !crate::engine::ENGINES.contains(&engine.as_str()) {
+        return Err("Invalid transcription engine".to_string());
+    }
+    let mut s = settings::load_settings(&app);
+    let previous = std::mem::replace(&mut s.engine, engine.clone());
+    if previous == engine {
+        return Ok(());
+    }
+    settings::save_settings(&app, &s)?;
+    reload_engine_or_revert(&app, move |s| s.engine = previous)
+        .await
+        .map_err(|e| format!("Failed to load the model with the {} engine: {}", engine, e))?;
+
+    log::info!("Transcription engine switched to '{}'", engine);
+    let _ = app.emit("engine-changed", engine);
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_model_precision(app: AppHandle) -> Result<String, String> {
+    let s = settings::load_settings(&app);
+    Ok(s.engine_params().precision)
+}
+
+/// FP32 is more accurate but slower, and needs the model's non-quantized ONNX files.
+#[tauri::command]
+pub async fn set_model_precision(app: AppHandle, precision: String) -> Result<(), String> {
+    let allowed = ["int8", "fp32"];
+    if !allowed.contains(&precision.as_str()) {
+        return Err("Invalid model precision".to_string());
+    }
+    let mut s = settings::load_settings(&app);
+    let previous = std::mem::replace(&mut s.engine_params_mut().precision, precision.clone());
+    if previous == precision {
+        return Ok(());
+    }
+    settings::save_settings(&app, &s)?;
+    reload_engine_or_revert(&app, move |s| s.engine_params_mut().precision = previous)
+        .await
+        .map_err(|e| format!("Failed to load the {} model: {}", precision, e))
+}
+
+#[tauri::command]
+pub fn get_onnx_runtime_settings(app: AppHandle) -> Result<settings::OnnxRuntimeSettings, String> {
+    let s = settings::load_settings(&app);
+    Ok(s.engine_params().onnx_runtime)
+}
+
+#[tauri::command]
+pub async fn set_onnx_runtime_settings(
+    app: AppHandle,
+    onnx_runtime: settings::OnnxRuntimeSettings,
+) -> Result<(), String> {
+    let allowed = ["disable", "basic", "extended", "all"];
+    if !allowed.contains(&onnx_runtime.optimization_level.as_str()) {
+        return Err("Invalid optimization level".to_string());
+    }
+    let max_threads = std::thread::available_parallelism().map_or(1, |n| n.get());
+    if onnx_runtime.intra_threads > max_threads || onnx_runtime.inter_threads > max_threads {
+        return Err(format!("Thread counts must be at most {}", max_threads));
+    }
+    let mut s = settings::load_settings(&app);
+    let previous = std::mem::replace(
+        &mut s.engine_params_mut().onnx_runtime,
+        onnx_runtime.clone(),
+    );
+    if previous == onnx_runtime {
+        return Ok(());
+    }
+    settings::save_settings(&app, &s)?;
+    reload_engine_or_revert(&app, move |s| s.engine_params_mut().onnx_runtime = previous)
+        .await
+        .map_err(|e| format!("Failed to load model with these settings: {}", e))
+}
+
+/// Transcribe every WAV file of a folder, reporting "folder-import-progress" events.
+#[tauri::command]
+pub async fn transcribe_folder(
+    app: AppHandle,
+    folder: String,
+    write_text_files: bool,
+) -> Result<Vec<crate::folder_import::FileTranscription>, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        crate::folder_import::transcribe_folder(
+            &app,
+            std::path::Path::new(&folder),
+            write_text_files,
+        )
+    })
+    .await
+    .map_err(|e| e.to_string())?
+    .map_err(|e| format!("{:#}", e))
+}
+
+#[tauri::command]
+pub fn get_model_idle_unload_minutes(app: AppHandle) -> Result<u64, String> {
+    let s = settings::load_settings(&app);
+    Ok(s.model_idle_unload_minutes)
+}
+
+#[tauri::command]
+pub fn set_model_idle_unload_minutes(app: AppHandle, minutes: u64) -> Result<(), String> {
+    let mut s = settings::load_settings(&app);
+    s.model_idle_unload_minutes = minutes;
+    settings::save_settings(&app, &s)
+}
+
+#[tauri::command]
+pub fn get_prewarm_model_on_record(app: AppHandle) -> Result<bool, String> {
+    let s = settings::load_settings(&app);
+    Ok(s.prewarm_model_on_record)
+}
+
+#[tauri::command]
+pub fn set_prewarm_model_on_record(app: AppHandle, enabled: bool) -> Result<(), String> {
+    let mut s = settings::load_settings(&app);
+    s.prewarm_model_on_record = enabled;
+    settings::save_settings(&app, &s)
+}
+
+#[tauri::command]
+pub fn get_memory_usage() -> Result<crate::memory::MemoryUsage, String> {
+    Ok(crate::memory::memory_usage())
+}
+
+#[tauri::command]
+pub fn get_loaded_model() -> Result<Option<crate::audio::LoadedModelInfo>, String> {
+    Ok(crate::audio::loaded_model())
+}
+
+#[tauri::command]
+pub fn get_model_source(app: AppHandle) -> Result<String, String> {
+    let s = settings::load_settings(&app);
+    Ok(s.model_source)
+}
+
+#[tauri::command]
+pub fn set_model_source(app: AppHandle, source: String) -> Result<(), String> {
+    let source = source.trim().trim_end_matches('/').to_string();
+    if !source.is_empty() && !source.starts_with("http://") && !source.starts_with("https://") {
+        return Err("The model source must be an http(s) URL".to_string());
+    }
+    let mut s = settings::load_settings(&app);
+    s.model_source = source;
+    settings::save_settings(&app, &s)
+}
+
 #[tauri::command]
 pub fn get_recent_transcriptions(app: AppHandle) -> Result<Vec<HistoryEntry>, String> {
     history::get_recent_transcriptions(&app).map_err(|e| format!("{:#}", e))
@@ -36,13 +294,13 @@ pub fn get_record_shortcut(app: AppHandle) -> Result<String, String> {
 
 #[tauri::command]
 pub fn set_record_shortcut(app: AppHandle, binding: String) -> Result<String, String> {
-    let keys = parse_binding_keys(&binding);
-    if keys.is_empty() {
-        return Err("Invalid shortcut".to_string());
-    }
+    let mut s = settings::load_settings(&app);
+    let mut taken = custom_shortcut_keys(&s);
+    taken.push(parse_binding_keys(&s.last_transcript_shortcut));
+    let mode = app.state::<ShortcutMatchMode>().get();
+    let keys = validate_binding(&binding, &taken, mode)?;
     let normalized = keys_to_string(&keys);
 
-    let mut s = settings::load_settings(&app);
     s.record_shortcut = normalized.clone();
     settings::save_settings(&app, &s)?;
 
@@ -68,6 +326,22 @@ pub fn get_dictionary(app: AppHandle) -> Result<Vec<String>, String> {
     Ok(s.dictionary)
 }
 
+#[tauri::command]
+pub fn get_dictionary_skip_confidence(app: AppHandle) -> Result<f32, String> {
+    let s = settings::load_settings(&app);
+    Ok(s.dictionary_skip_confidence)
+}
+
+#[tauri::command]
+pub fn set_dictionary_skip_confidence(app: AppHandle, confidence: f32) -> Result<(), String> {
+    if !(0.0..=1.0).contains(&confidence) {
+        return Err("Confidence must be between 0 and 1".to_string());
+    }
+    let mut s = settings::load_settings(&app);
+    s.dictionary_skip_confidence = confidence;
+    settings::save_settings(&app, &s)
+}
+
 #[tauri::command]
 pub fn get_last_transcript_shortcut(app: AppHandle) -> Result<String, String> {
     let s = settings::load_settings(&app);
@@ -76,13 +350,13 @@ pub fn get_last_transcript_shortcut(app: AppHandle) -> Result<String, String> {
 
 #[tauri::command]
 pub fn set_last_transcript_shortcut(app: AppHandle, binding: String) -> Result<String, String> {
-    let keys = parse_binding_keys(&binding);
-    if keys.is_empty() {
-        return Err("Invalid shortcut".to_string());
-    }
+    let mut s = settings::load_settings(&app);
+    let mut taken = custom_shortcut_keys(&s);
+    taken.push(parse_binding_keys(&s.record_shortcut));
+    let mode = app.state::<ShortcutMatchMode>().get();
+    let keys = validate_binding(&binding, &taken, mode)?;
     let normalized = keys_to_string(&keys);
 
-    let mut s = settings::load_settings(&app);
     s.last_transcript_shortcut = normalized.clone();
     settings::save_settings(&app, &s)?;
 
@@ -94,12 +368,35 @@ pub fn set_last_transcript_shortcut(app: AppHandle, binding: String) -> Result<S
 #[tauri::command]
 pub fn suspend_transcription(app: AppHandle) -> Result<(), String> {
     app.state::<TranscriptionSuspended>().set(true);
+    let _ = app.emit("transcription-suspended", true);
     Ok(())
 }
 
 #[tauri::command]
 pub fn resume_transcription(app: AppHandle) -> Result<(), String> {
     app.state::<TranscriptionSuspended>().set(false);
+    let _ = app.emit("transcription-suspended", false);
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_microphones() -> Result<Vec<String>, String> {
+    Ok(crate::audio::list_microphones())
+}
+
+#[tauri::command]
+pub fn get_microphone(app: AppHandle) -> Result<Option<String>, String> {
+    let s = settings::load_settings(&app);
+    Ok(s.microphone)
+}
+
+/// `None` follows the system default input device.
+#[tauri::command]
+pub fn set_microphone(app: AppHandle, microphone: Option<String>) -> Result<(), String> {
+    let mut s = settings::load_settings(&app);
+    s.microphone = microphone.filter(|name| !name.trim().is_empty());
+    settings::save_settings(&app, &s)?;
+    let _ = app.emit("microphone-changed", s.microphone);
     Ok(())
 }
 
@@ -138,7 +435,7 @@ pub fn get_overlay_position(app: AppHandle) -> Result<String, String> {
 
 #[tauri::command]
 pub fn set_overlay_position(app: AppHandle, position: String) -> Result<(), String> {
-    let allowed = ["top", "bottom"];
+    let allowed = ["top", "bottom", "custom"];
     if !allowed.contains(&position.as_str()) {
         return Err("Invalid overlay position".to_string());
     }
@@ -149,6 +446,64 @@ pub fn set_overlay_position(app: AppHandle, position: String) -> Result<(), Stri
     res
 }
 
+#[tauri::command]
+pub fn get_overlay_style(app: AppHandle) -> Result<String, String> {
+    let s = settings::load_settings(&app);
+    Ok(s.overlay_style)
+}
+
+#[tauri::command]
+pub fn set_overlay_style(app: AppHandle, style: String) -> Result<(), String> {
+    let allowed = ["compact", "expanded"];
+    if !allowed.contains(&style.as_str()) {
+        return Err("Invalid overlay style".to_string());
+    }
+    let mut s = settings::load_settings(&app);
+    s.overlay_style = style;
+    let res = settings::save_settings(&app, &s);
+    crate::overlay::reset_overlay_size(&app);
+    let _ = app.emit("overlay-style-changed", &s.overlay_style);
+    res
+}
+
+#[tauri::command]
+pub fn get_overlay_monitor(app: AppHandle) -> Result<String, String> {
+    let s = settings::load_settings(&app);
+    Ok(s.overlay_monitor)
+}
+
+#[tauri::command]
+pub fn set_overlay_monitor(app: AppHandle, monitor: String) -> Result<(), String> {
+    let allowed = ["primary", "cursor", "focused"];
+    if !allowed.contains(&monitor.as_str())
+        && !crate::overlay::list_monitors(&app)
+            .iter()
+            .any(|m| m.name == monitor)
+    {
+        return Err("Invalid overlay monitor".to_string());
+    }
+    let mut s = settings::load_settings(&app);
+    s.overlay_monitor = monitor;
+    let res = settings::save_settings(&app, &s);
+    crate::overlay::update_overlay_position(&app);
+    res
+}
+
+#[tauri::command]
+pub fn get_monitors(app: AppHandle) -> Result<Vec<crate::overlay::MonitorInfo>, String> {
+    Ok(crate::overlay::list_monitors(&app))
+}
+
+/// Called once the user is done dragging the overlay.
+#[tauri::command]
+pub fn save_overlay_custom_position(app: AppHandle) -> Result<(), String> {
+    let position = crate::overlay::save_custom_position(&app)?;
+    let mut s = settings::load_settings(&app);
+    s.overlay_position = "custom".to_string();
+    s.overlay_custom_position = Some(position);
+    settings::save_settings(&app, &s)
+}
+
 #[tauri::command]
 pub fn get_api_enabled(app: AppHandle) -> Result<bool, String> {
     let s = settings::load_settings(&app);
@@ -193,6 +548,298 @@ pub fn start_http_api_server(app: AppHandle) -> Result<String, String> {
 pub fn stop_http_api_server(app: AppHandle) -> Result<(), String> {
     let state = app.state::<HttpApiState>();
     state.stop();
-    eprintln!("HTTP API server stop signal sent");
+    log::info!("HTTP API server stop signal sent");
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_itn_enabled(app: AppHandle) -> Result<bool, String> {
+    let s = settings::load_settings(&app);
+    Ok(s.itn_enabled)
+}
+
+#[tauri::command]
+pub fn set_itn_enabled(app: AppHandle, enabled: bool) -> Result<(), String> {
+    let mut s = settings::load_settings(&app);
+    s.itn_enabled = enabled;
+    settings::save_settings(&app, &s)
+}
+
+#[tauri::command]
+pub fn get_itn_language(app: AppHandle) -> Result<String, String> {
+    let s = settings::load_settings(&app);
+    Ok(s.itn_language)
+}
+
+#[tauri::command]
+pub fn set_itn_language(app: AppHandle, language: String) -> Result<(), String> {
+    let allowed = ["en", "fr"];
+    if !allowed.contains(&language.as_str()) {
+        return Err("Invalid ITN language".to_string());
+    }
+    let mut s = settings::load_settings(&app);
+    s.itn_language = language;
+    settings::save_settings(&app, &s)
+}
+
+#[tauri::command]
+pub fn get_language_detection_enabled(app: AppHandle) -> Result<bool, String> {
+    let s = settings::load_settings(&app);
+    Ok(s.language_detection_enabled)
+}
+
+#[tauri::command]
+pub fn set_language_detection_enabled(app: AppHandle, enabled: bool) -> Result<(), String> {
+    let mut s = settings::load_settings(&app);
+    s.language_detection_enabled = enabled;
+    settings::save_settings(&app, &s)
+}
+
+#[tauri::command]
+pub fn get_filler_removal_enabled(app: AppHandle) -> Result<bool, String> {
+    let s = settings::load_settings(&app);
+    Ok(s.filler_removal_enabled)
+}
+
+#[tauri::command]
+pub fn set_filler_removal_enabled(app: AppHandle, enabled: bool) -> Result<(), String> {
+    let mut s = settings::load_settings(&app);
+    s.filler_removal_enabled = enabled;
+    settings::save_settings(&app, &s)
+}
+
+#[tauri::command]
+pub fn get_filler_words(app: AppHandle) -> Result<HashMap<String, FillerWords>, String> {
+    let s = settings::load_settings(&app);
+    Ok(s.filler_words)
+}
+
+#[tauri::command]
+pub fn set_filler_words(
+    app: AppHandle,
+    filler_words: HashMap<String, FillerWords>,
+) -> Result<(), String> {
+    let mut s = settings::load_settings(&app);
+    s.filler_words = filler_words;
+    settings::save_settings(&app, &s)
+}
+
+#[tauri::command]
+pub fn get_output_strategy(app: AppHandle) -> Result<OutputStrategy, String> {
+    let s = settings::load_settings(&app);
+    Ok(s.output_strategy)
+}
+
+#[tauri::command]
+pub fn set_output_strategy(app: AppHandle, strategy: OutputStrategy) -> Result<(), String> {
+    let mut s = settings::load_settings(&app);
+    s.output_strategy = strategy;
+    settings::save_settings(&app, &s)
+}
+
+/// macOS always pastes with Cmd+V, see `clipboard::send_paste`.
+const MACOS_PASTE_SHORTCUT: &str = "The paste shortcut is always Cmd+V on macOS";
+
+#[tauri::command]
+pub fn get_paste_shortcut(app: AppHandle) -> Result<String, String> {
+    let s = settings::load_settings(&app);
+    Ok(s.paste_shortcut)
+}
+
+#[tauri::command]
+pub fn set_paste_shortcut(app: AppHandle, binding: String) -> Result<String, String> {
+    if cfg!(target_os = "macos") {
+        return Err(MACOS_PASTE_SHORTCUT.to_string());
+    }
+    let keys = parse_binding_keys(&binding);
+    if keys.is_empty() || !keys.iter().all(|&k| is_keyboard_key(k)) {
+        return Err("Invalid shortcut".to_string());
+    }
+    let normalized = keys_to_string(&keys);
+
+    let mut s = settings::load_settings(&app);
+    s.paste_shortcut = normalized.clone();
+    settings::save_settings(&app, &s)?;
+
+    Ok(normalized)
+}
+
+#[tauri::command]
+pub fn get_output_profiles(app: AppHandle) -> Result<Vec<OutputProfile>, String> {
+    let s = settings::load_settings(&app);
+    Ok(s.output_profiles)
+}
+
+#[tauri::command]
+pub fn set_output_profiles(app: AppHandle, profiles: Vec<OutputProfile>) -> Result<(), String> {
+    let allowed_casings = ["keep", "lower", "snake_case", "camel_case"];
+    let allowed_sentence_modes = ["keep", "sentence", "continue"];
+    for p in &profiles {
+        if let Some(shortcut) = &p.paste_shortcut {
+            let keys = parse_binding_keys(shortcut);
+            if keys.is_empty() || !keys.iter().all(|&k| is_keyboard_key(k)) {
+                return Err(format!("Invalid paste shortcut in profile '{}'", p.name));
+            }
+        }
+        if !p.casing.is_empty() && !allowed_casings.contains(&p.casing.as_str()) {
+            return Err(format!("Invalid casing in profile '{}'", p.name));
+        }
+        if !p.sentence_mode.is_empty()
+            && !allowed_sentence_modes.contains(&p.sentence_mode.as_str())
+        {
+            return Err(format!("Invalid sentence mode in profile '{}'", p.name));
+        }
+    }
+    let mut s = settings::load_settings(&app);
+    s.output_profiles = profiles;
+    settings::save_settings(&app, &s)?;
+    crate::tray_icon::refresh_menu(&app);
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_record_mode(app: AppHandle) -> Result<String, String> {
+    let s = settings::load_settings(&app);
+    Ok(s.record_mode)
+}
+
+#[tauri::command]
+pub fn set_record_mode(app: AppHandle, mode: String) -> Result<(), String> {
+    let record_mode = RecordMode::parse(&mode).ok_or("Invalid record mode")?;
+    let mut s = settings::load_settings(&app);
+    s.record_mode = mode;
+    settings::save_settings(&app, &s)?;
+
+    app.state::<RecordShortcutMode>().set(record_mode);
+
     Ok(())
 }
+
+#[tauri::command]
+pub fn get_max_recording_seconds(app: AppHandle) -> Result<u64, String> {
+    let s = settings::load_settings(&app);
+    Ok(s.max_recording_seconds)
+}
+
+#[tauri::command]
+pub fn set_max_recording_seconds(app: AppHandle, seconds: u64) -> Result<(), String> {
+    let mut s = settings::load_settings(&app);
+    s.max_recording_seconds = seconds;
+    settings::save_settings(&app, &s)?;
+
+    app.state::<MaxRecordingDuration>().set(seconds);
+
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_shortcut_match_mode(app: AppHandle) -> Result<String, String> {
+    let s = settings::load_settings(&app);
+    Ok(s.shortcut_match_mode)
+}
+
+#[tauri::command]
+pub fn set_shortcut_match_mode(app: AppHandle, mode: String) -> Result<(), String> {
+    let match_mode = MatchMode::parse(&mode).ok_or("Invalid shortcut match mode")?;
+    let mut s = settings::load_settings(&app);
+    // Chords that coexist in exact mode can overlap in superset mode
+    let mut bindings = custom_shortcut_keys(&s);
+    bindings.push(parse_binding_keys(&s.record_shortcut));
+    bindings.push(parse_binding_keys(&s.last_transcript_shortcut));
+    for (i, keys) in bindings.iter().enumerate() {
+        if let Some(other) = bindings[..i]
+            .iter()
+            .find(|other| bindings_overlap(keys, other, match_mode))
+        {
+            return Err(format!(
+                "Shortcuts {} and {} conflict in {} mode",
+                keys_to_string(other),
+                keys_to_string(keys),
+                mode
+            ));
+        }
+    }
+    s.shortcut_match_mode = mode;
+    settings::save_settings(&app, &s)?;
+
+    app.state::<ShortcutMatchMode>().set(match_mode);
+
+    Ok(())
+}
+
+fn custom_shortcut_keys(s: &settings::AppSettings) -> Vec<Vec<i32>> {
+    s.custom_shortcuts
+        .iter()
+        .map(|b| parse_binding_keys(&b.binding))
+        .collect()
+}
+
+#[tauri::command]
+pub fn get_shortcut_actions() -> Vec<&'static str> {
+    ShortcutAction::NAMES.to_vec()
+}
+
+#[tauri::command]
+pub fn get_custom_shortcuts(app: AppHandle) -> Result<Vec<ShortcutBinding>, String> {
+    let s = settings::load_settings(&app);
+    Ok(s.custom_shortcuts)
+}
+
+#[tauri::command]
+pub fn set_custom_shortcuts(
+    app: AppHandle,
+    shortcuts: Vec<ShortcutBinding>,
+) -> Result<Vec<ShortcutBinding>, String> {
+    let mut s = settings::load_settings(&app);
+    let mut taken = vec![
+        parse_binding_keys(&s.record_shortcut),
+        parse_binding_keys(&s.last_transcript_shortcut),
+    ];
+
+    let mode = app.state::<ShortcutMatchMode>().get();
+    let mut normalized = Vec::with_capacity(shortcuts.len());
+    for shortcut in shortcuts {
+        if ShortcutAction::parse(&shortcut.action, shortcut.argument.as_deref()).is_none() {
+            return Err(format!("Invalid shortcut action: {}", shortcut.action));
+        }
+        let keys = validate_binding(&shortcut.binding, &taken, mode)?;
+        normalized.push(ShortcutBinding {
+            binding: keys_to_string(&keys),
+            ..shortcut
+        });
+        taken.push(keys);
+    }
+
+    s.custom_shortcuts = normalized.clone();
+    settings::save_settings(&app, &s)?;
+
+    app.state::<CustomShortcuts>().set(&normalized);
+
+    Ok(normalized)
+}
+
+#[tauri::command]
+pub fn get_linux_input_backend(app: AppHandle) -> Result<String, String> {
+    let s = settings::load_settings(&app);
+    Ok(s.linux_input_backend)
+}
+
+/// Takes effect on the next start.
+#[tauri::command]
+pub fn set_linux_input_backend(app: AppHandle, backend: String) -> Result<(), String> {
+    if !["auto", "x11", "evdev", "portal"].contains(&backend.as_str()) {
+        return Err("Invalid input backend".to_string());
+    }
+    let mut s = settings::load_settings(&app);
+    s.linux_input_backend = backend;
+    settings::save_settings(&app, &s)
+}
+
+/// The backend currently listening for shortcuts, `None` outside Linux.
+#[tauri::command]
+pub fn get_active_input_backend() -> Option<String> {
+    #[cfg(target_os = "linux")]
+    return crate::shortcuts::active_backend().map(|b| b.name().to_string());
+    #[cfg(not(target_os = "linux"))]
+    None
+}
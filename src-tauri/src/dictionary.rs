@@ -1,3 +1,4 @@
+use crate::engine::transcription_engine::TranscriptionSegment;
 use rphonetic::{BeiderMorseBuilder, ConfigFiles, LanguageSet};
 use std::{
     path::PathBuf,
@@ -20,12 +21,43 @@ impl Dictionary {
 }
 
 /**
- * Use phonetic algorithm to fix the transcription
+ * For each word, whether the model is at least `threshold` sure of it, in which case
+ * the dictionary leaves it alone. A threshold of 0 disables the check.
+ */
+pub fn confident_words(words: &[TranscriptionSegment], threshold: f32) -> Vec<bool> {
+    words
+        .iter()
+        .map(|word| threshold > 0.0 && word.confidence >= threshold)
+        .collect()
+}
+
+/**
+ * Beider-Morse rules matching a language code, for the languages that have some
+ */
+fn beider_morse_language(code: &str) -> Option<&'static str> {
+    match code {
+        "en" => Some("english"),
+        "fr" => Some("french"),
+        "de" => Some("german"),
+        "es" => Some("spanish"),
+        "it" => Some("italian"),
+        "pt" => Some("portuguese"),
+        "nl" => Some("dutch"),
+        _ => None,
+    }
+}
+
+/**
+ * Use phonetic algorithm to fix the transcription, skipping the words flagged in
+ * `confident_words`, one flag per word of the transcription.
+ * Words are compared with the rules of `language`, French and English when unknown.
  */
 pub fn fix_transcription_with_dictionary(
     transcription: String,
     dictionary: Vec<String>,
     cc_rules_path: PathBuf,
+    confident_words: &[bool],
+    language: Option<&str>,
 ) -> String {
     if dictionary.is_empty() {
         return transcription;
@@ -36,7 +68,10 @@ pub fn fix_transcription_with_dictionary(
     let beider_morse = builder.build();
 
     // TODO: Make user able to choose the languages for each word
-    let langs = LanguageSet::from(vec!["french", "english"]);
+    let langs = match language.and_then(beider_morse_language) {
+        Some(language) => LanguageSet::from(vec![language]),
+        None => LanguageSet::from(vec!["french", "english"]),
+    };
 
     // Prepare dictionary words to be encoded phonetically
     let mut encoded_dict = Vec::new();
@@ -48,15 +83,29 @@ pub fn fix_transcription_with_dictionary(
     // Split transcription into words
     let mut corrected_transcription = transcription.clone();
     let words: Vec<&str> = transcription.split_whitespace().collect();
+    // The flags come from the word segments, they only apply when they line up
+    if !confident_words.is_empty() && confident_words.len() != words.len() {
+        log::warn!(
+            "{} confidence flags for {} words, checking every word",
+            confident_words.len(),
+            words.len()
+        );
+    }
+    let aligned = confident_words.len() == words.len();
 
-    for word in words {
+    for (i, word) in words.into_iter().enumerate() {
+        if aligned && confident_words[i] {
+            continue;
+        }
         let candidate = beider_morse.encode_with_languages(word, &langs);
         let candidate_codes: Vec<&str> = candidate.split('|').collect();
         for (dict_word, dict_code) in &encoded_dict {
             let dict_codes: Vec<&str> = dict_code.split('|').collect();
-            println!(
+            log::trace!(
                 "Dict word: {:?}, Dict code: {:?}, Candidate: {:?}",
-                dict_word, dict_code, candidate
+                dict_word,
+                dict_code,
+                candidate
             );
             if dict_codes.iter().any(|dc| candidate_codes.contains(dc)) {
                 corrected_transcription = corrected_transcription.replace(word, dict_word);
@@ -88,17 +137,49 @@ pub fn get_cc_rules_path(app_handle: &AppHandle) -> anyhow::Result<PathBuf> {
     for path_result in possible_paths {
         match path_result {
             Ok(ref ccrules_path) if ccrules_path.exists() => {
-                println!("Model found at: {}", ccrules_path.display());
+                log::info!("Model found at: {}", ccrules_path.display());
                 return Ok(ccrules_path.clone());
             }
             Ok(ref ccrules_path) => {
-                println!("Model not found at: {}", ccrules_path.display());
+                log::warn!("Model not found at: {}", ccrules_path.display());
             }
             Err(e) => {
-                println!("Error resolving path: {:?}", e);
+                log::warn!("Error resolving path: {:?}", e);
             }
         }
     }
 
     anyhow::bail!("Bundled cc_rules not found in any known location");
 }
+
+#[cfg(test)]
+mod tests {
+    use super::confident_words;
+    use crate::engine::transcription_engine::TranscriptionSegment;
+
+    fn words(confidences: &[f32]) -> Vec<TranscriptionSegment> {
+        confidences
+            .iter()
+            .map(|&confidence| TranscriptionSegment {
+                start: 0.0,
+                end: 0.0,
+                text: "word".to_string(),
+                confidence,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn flags_words_at_or_above_the_threshold() {
+        assert_eq!(
+            confident_words(&words(&[0.95, 0.4, 0.9, 0.89]), 0.9),
+            [true, false, true, false]
+        );
+    }
+
+    #[test]
+    fn zero_threshold_flags_nothing() {
+        assert_eq!(confident_words(&words(&[1.0, 0.0]), 0.0), [false, false]);
+        assert_eq!(confident_words(&[], 0.9), Vec::<bool>::new());
+    }
+}
@@ -0,0 +1,288 @@
+use crate::engine::transcription_engine::{TranscriptionResult, TranscriptionSegment};
+use crate::settings::AppSettings;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Filler words of a language.
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[serde(default)]
+pub struct FillerWords {
+    /// Always removed ("uh", "um", "euh")
+    pub hesitations: Vec<String>,
+    /// Only removed when set off by commas ("like," / ", genre,"),
+    /// so that "I like it" is left untouched
+    pub discourse_markers: Vec<String>,
+    /// Words correctly said twice in a row ("he had had enough", "nous nous sommes"),
+    /// so their repeats are not stutters
+    pub legal_doubles: Vec<String>,
+}
+
+pub fn default_filler_words() -> HashMap<String, FillerWords> {
+    let to_vec = |words: &[&str]| words.iter().map(|w| w.to_string()).collect();
+    HashMap::from([
+        (
+            "en".to_string(),
+            FillerWords {
+                hesitations: to_vec(&["uh", "um", "uhm", "er", "erm", "hmm", "mm"]),
+                discourse_markers: to_vec(&["like", "you know", "i mean"]),
+                legal_doubles: to_vec(&["that", "had", "do"]),
+            },
+        ),
+        (
+            "fr".to_string(),
+            FillerWords {
+                hesitations: to_vec(&["euh", "heu", "hum", "bah", "ben"]),
+                discourse_markers: to_vec(&["genre", "quoi", "du coup", "en fait"]),
+                legal_doubles: to_vec(&["nous", "vous"]),
+            },
+        ),
+    ])
+}
+
+/// Remove disfluencies from a word-level transcription if enabled in settings.
+/// Only the fillers of `language` are removed, or of the configured language when it is
+/// unknown: a hesitation of one language can be a word of another ("Call Ben").
+pub fn clean_with_settings(
+    settings: &AppSettings,
+    result: TranscriptionResult,
+    language: Option<&str>,
+) -> TranscriptionResult {
+    if !settings.filler_removal_enabled {
+        return result;
+    }
+    let language = language.unwrap_or(&settings.itn_language);
+    let segments = remove_disfluencies(&result.segments, settings.filler_words.get(language));
+    if segments.len() == result.segments.len() {
+        return result;
+    }
+    TranscriptionResult {
+        text: join_words(&segments),
+        segments,
+        confidence: result.confidence,
+        language: result.language,
+    }
+}
+
+/// Remove filler words and stutter repeats ("I I think") from word-level segments.
+/// The timestamps of removed words are dropped along with them.
+pub fn remove_disfluencies<'a>(
+    words: &[TranscriptionSegment],
+    fillers: impl IntoIterator<Item = &'a FillerWords>,
+) -> Vec<TranscriptionSegment> {
+    let mut hesitations: Vec<Vec<String>> = Vec::new();
+    let mut markers: Vec<Vec<String>> = Vec::new();
+    let mut legal_doubles: Vec<String> = Vec::new();
+    for f in fillers {
+        hesitations.extend(f.hesitations.iter().map(|p| split_phrase(p)));
+        markers.extend(f.discourse_markers.iter().map(|p| split_phrase(p)));
+        legal_doubles.extend(f.legal_doubles.iter().map(|w| normalize_word(w)));
+    }
+
+    let normalized: Vec<String> = words.iter().map(|w| normalize_word(&w.text)).collect();
+    let mut keep = vec![true; words.len()];
+    let mut strip_comma = vec![false; words.len()];
+
+    let mut i = 0;
+    while i < words.len() {
+        let set_off = |len: usize| {
+            let last = &words[i + len - 1].text;
+            (i == 0 || words[i - 1].text.ends_with(','))
+                && (last.ends_with(',') || ends_sentence(last) || i + len == words.len())
+                && (i > 0 || last.ends_with(','))
+        };
+        if let Some(len) = matching_len(&normalized[i..], &markers).filter(|&len| set_off(len)) {
+            // "It was, like, huge" -> "It was huge"
+            if i > 0 && words[i + len - 1].text.ends_with(',') {
+                strip_comma[i - 1] = true;
+            }
+            keep[i..i + len].iter_mut().for_each(|k| *k = false);
+            i += len;
+            continue;
+        }
+
+        if let Some(len) = matching_len(&normalized[i..], &hesitations) {
+            keep[i..i + len].iter_mut().for_each(|k| *k = false);
+            i += len;
+            continue;
+        }
+
+        // Stutter: drop the first of two identical consecutive words
+        let repeated = normalized.get(i + 1).is_some_and(|next| {
+            !next.is_empty() && *next == normalized[i] && !legal_doubles.contains(next)
+        });
+        if repeated && !ends_sentence(&words[i].text) {
+            keep[i] = false;
+        }
+        i += 1;
+    }
+
+    let mut out: Vec<TranscriptionSegment> = Vec::with_capacity(words.len());
+    let mut capitalize_next = false;
+    for (i, word) in words.iter().enumerate() {
+        if !keep[i] {
+            let at_sentence_start = out.last().is_none_or(|w| ends_sentence(&w.text));
+            // Keep the sentence start capitalized: "Um, so we..." -> "So we..."
+            capitalize_next |= at_sentence_start && starts_uppercase(&word.text);
+            // Keep the sentence end: "I think, um." -> "I think."
+            if ends_sentence(&word.text) {
+                if let Some(prev) = out.last_mut().filter(|w| !ends_sentence(&w.text)) {
+                    let trimmed = prev.text.trim_end_matches(',').len();
+                    prev.text.truncate(trimmed);
+                    prev.text.push_str(trailing_punctuation(&word.text));
+                }
+            }
+            continue;
+        }
+
+        let mut text = if std::mem::take(&mut capitalize_next) {
+            capitalize(&word.text)
+        } else {
+            word.text.clone()
+        };
+        if strip_comma[i] {
+            text.truncate(text.trim_end_matches(',').len());
+        }
+        out.push(TranscriptionSegment {
+            start: word.start,
+            end: word.end,
+            text,
+            confidence: word.confidence,
+        });
+    }
+
+    out
+}
+
+pub fn join_words(words: &[TranscriptionSegment]) -> String {
+    words
+        .iter()
+        .map(|w| w.text.as_str())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn split_phrase(phrase: &str) -> Vec<String> {
+    phrase.split_whitespace().map(normalize_word).collect()
+}
+
+fn normalize_word(word: &str) -> String {
+    word.trim_matches(|c: char| !c.is_alphanumeric())
+        .to_lowercase()
+}
+
+fn matching_len(words: &[String], phrases: &[Vec<String>]) -> Option<usize> {
+    phrases
+        .iter()
+        .filter(|p| !p.is_empty() && words.len() >= p.len() && words[..p.len()] == p[..])
+        .map(|p| p.len())
+        .max()
+}
+
+fn ends_sentence(word: &str) -> bool {
+    word.ends_with(['.', '?', '!'])
+}
+
+fn starts_uppercase(word: &str) -> bool {
+    word.chars().next().is_some_and(|c| c.is_uppercase())
+}
+
+fn trailing_punctuation(word: &str) -> &str {
+    let trimmed = word.trim_end_matches(['.', '?', '!']);
+    &word[trimmed.len()..]
+}
+
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn words(text: &str) -> Vec<TranscriptionSegment> {
+        text.split_whitespace()
+            .enumerate()
+            .map(|(i, word)| TranscriptionSegment {
+                start: i as f32 * 0.3,
+                end: i as f32 * 0.3 + 0.25,
+                text: word.to_string(),
+                confidence: 1.0,
+            })
+            .collect()
+    }
+
+    fn clean(text: &str, language: &str) -> String {
+        let fillers = default_filler_words();
+        join_words(&remove_disfluencies(&words(text), fillers.get(language)))
+    }
+
+    #[test]
+    fn stutters_are_removed() {
+        let cases = [
+            ("I I think so.", "en", "I think so."),
+            ("the the meeting is at noon", "en", "the meeting is at noon"),
+            ("je je pense que oui", "fr", "je pense que oui"),
+            ("It ended. Ended there.", "en", "It ended. Ended there."),
+        ];
+        for (input, language, expected) in cases {
+            assert_eq!(clean(input, language), expected, "{input}");
+        }
+    }
+
+    #[test]
+    fn legal_doubles_are_kept() {
+        let cases = [
+            ("nous nous sommes vus hier", "fr"),
+            ("vous vous trompez", "fr"),
+            ("I think that that works", "en"),
+            ("he had had enough", "en"),
+            ("what they do do matters", "en"),
+        ];
+        for (input, language) in cases {
+            assert_eq!(clean(input, language), input);
+        }
+    }
+
+    #[test]
+    fn legal_doubles_follow_the_language() {
+        // A legal double of one language is still a stutter in another
+        assert_eq!(clean("je vous vous dis", "en"), "je vous dis");
+        assert_eq!(clean("I had had enough", "fr"), "I had enough");
+
+        let mut fillers = default_filler_words();
+        fillers
+            .get_mut("en")
+            .unwrap()
+            .legal_doubles
+            .push("is".to_string());
+        let cleaned = remove_disfluencies(&words("what it is is fine"), fillers.get("en"));
+        assert_eq!(join_words(&cleaned), "what it is is fine");
+    }
+
+    #[test]
+    fn fillers_are_removed() {
+        let cases = [
+            ("Um, so we start tomorrow.", "en", "So we start tomorrow."),
+            ("It was, like, huge.", "en", "It was huge."),
+            ("I like it.", "en", "I like it."),
+            (
+                "Euh je sais pas, genre, demain.",
+                "fr",
+                "Je sais pas demain.",
+            ),
+        ];
+        for (input, language, expected) in cases {
+            assert_eq!(clean(input, language), expected, "{input}");
+        }
+    }
+
+    #[test]
+    fn fillers_of_other_languages_are_kept() {
+        assert_eq!(clean("Call Ben tomorrow.", "en"), "Call Ben tomorrow.");
+        assert_eq!(clean("Call Ben tomorrow.", "de"), "Call Ben tomorrow.");
+    }
+}
@@ -0,0 +1,328 @@
+// CTC engine for NeMo-style models exported to ONNX
+
+use super::{
+    engine::{ParakeetModelParams, QuantizationType, TimestampGranularity},
+    model::{
+        max_probability, tokens_to_text, ParakeetError, ParakeetModel, TimestampedResult,
+        WINDOW_SIZE,
+    },
+    timestamp::convert_timestamps,
+    transcription_engine::{TranscriptionEngine, TranscriptionResult},
+};
+use ndarray::{Array1, Array2, ArrayD, ArrayViewD, Axis};
+use ort::inputs;
+use ort::session::Session;
+use ort::value::TensorRef;
+use std::path::Path;
+
+/// Preprocessors tried in order, the mel feature count must match the model's.
+const PREPROCESSORS: [&str; 2] = ["nemo128", "nemo80"];
+
+/// CTC models load with the same parameters as Parakeet models. Both are ONNX
+/// exports whose loading is only tuned by the quantization and the ONNX Runtime
+/// session options, and the preprocessor is found from the model files. The
+/// settings still keep one set of parameters per engine, see `EngineParams`.
+pub type CtcModelParams = ParakeetModelParams;
+
+/// Parameters for configuring CTC inference behavior.
+#[derive(Debug, Clone, Default)]
+pub struct CtcInferenceParams {
+    /// The granularity level for timestamp generation
+    pub timestamp_granularity: TimestampGranularity,
+}
+
+/// A CTC acoustic model: a preprocessor producing mel features and an
+/// encoder producing per-frame token log probabilities, decoded greedily.
+///
+/// The model directory holds `nemo128.onnx` (or `nemo80.onnx`), `model.onnx`
+/// (or `model.int8.onnx`) and `vocab.txt` with a `<blk>` token.
+pub struct CtcModel {
+    preprocessor: Session,
+    model: Session,
+    vocab: Vec<String>,
+    blank_idx: usize,
+    onnx_files: Vec<String>,
+}
+
+impl CtcModel {
+    pub fn new<P: AsRef<Path>>(
+        model_dir: P,
+        params: &CtcModelParams,
+    ) -> Result<Self, ParakeetError> {
+        let quantized = params.quantization == QuantizationType::Int8;
+        let preprocessor_name = PREPROCESSORS
+            .iter()
+            .find(|name| model_dir.as_ref().join(format!("{}.onnx", name)).exists())
+            .unwrap_or(&PREPROCESSORS[0]);
+        let (preprocessor, preprocessor_file) =
+            ParakeetModel::init_session(&model_dir, preprocessor_name, params, false)?;
+        let (model, model_file) =
+            ParakeetModel::init_session(&model_dir, "model", params, quantized)?;
+
+        let (vocab, blank_idx) = ParakeetModel::load_vocab(&model_dir)?;
+        log::info!(
+            "Loaded CTC vocabulary with {} tokens, blank_idx={}",
+            vocab.len(),
+            blank_idx
+        );
+
+        Ok(Self {
+            preprocessor,
+            model,
+            vocab,
+            blank_idx: blank_idx as usize,
+            onnx_files: vec![model_file, preprocessor_file],
+        })
+    }
+
+    pub fn onnx_files(&self) -> &[String] {
+        &self.onnx_files
+    }
+
+    pub fn transcribe_samples(
+        &mut self,
+        samples: Vec<f32>,
+    ) -> Result<TimestampedResult, ParakeetError> {
+        let samples_len = samples.len();
+        let waveforms = Array2::from_shape_vec((1, samples_len), samples)?.into_dyn();
+        let waveforms_lens = Array1::from_vec(vec![samples_len as i64]).into_dyn();
+
+        let (features, features_lens) =
+            self.preprocess(&waveforms.view(), &waveforms_lens.view())?;
+        let (log_probs, encoded_lens) = self.encode(&features.view(), &features_lens.view())?;
+
+        let features_len = features_lens.iter().next().copied().unwrap_or(0) as f32;
+        let encoded_len = encoded_lens.iter().next().copied().unwrap_or(0) as usize;
+        // Encoder frames span several 10 ms feature frames, depending on the model's subsampling
+        let frame_seconds = if encoded_len > 0 {
+            WINDOW_SIZE * features_len / encoded_len as f32
+        } else {
+            0.0
+        };
+
+        let log_probs = log_probs.index_axis(Axis(0), 0);
+        let mut tokens = Vec::new();
+        let mut timestamps = Vec::new();
+        let mut confidences = Vec::new();
+        for (t, token, confidence) in greedy_decode(log_probs, encoded_len, self.blank_idx) {
+            if let Some(text) = self.vocab.get(token) {
+                tokens.push(text.clone());
+                timestamps.push(t as f32 * frame_seconds);
+                confidences.push(confidence);
+            }
+        }
+
+        Ok(TimestampedResult {
+            text: tokens_to_text(&tokens),
+            timestamps,
+            tokens,
+            confidences,
+        })
+    }
+
+    fn preprocess(
+        &mut self,
+        waveforms: &ArrayViewD<f32>,
+        waveforms_lens: &ArrayViewD<i64>,
+    ) -> Result<(ArrayD<f32>, ArrayD<i64>), ParakeetError> {
+        let inputs = inputs![
+            "waveforms" => TensorRef::from_array_view(waveforms.view())?,
+            "waveforms_lens" => TensorRef::from_array_view(waveforms_lens.view())?,
+        ];
+        let outputs = self.preprocessor.run(inputs)?;
+
+        let features = outputs
+            .get("features")
+            .ok_or_else(|| ParakeetError::OutputNotFound("features".to_string()))?
+            .try_extract_array()?;
+        let features_lens = outputs
+            .get("features_lens")
+            .ok_or_else(|| ParakeetError::OutputNotFound("features_lens".to_string()))?
+            .try_extract_array()?;
+
+        Ok((features.to_owned(), features_lens.to_owned()))
+    }
+
+    /// Log probabilities as [batch, time, vocab], and the encoded lengths.
+    fn encode(
+        &mut self,
+        audio_signal: &ArrayViewD<f32>,
+        length: &ArrayViewD<i64>,
+    ) -> Result<(ArrayD<f32>, ArrayD<i64>), ParakeetError> {
+        // Exports name the log probabilities differently, they always come first
+        let output_name = self
+            .model
+            .outputs
+            .first()
+            .map(|output| output.name.clone())
+            .ok_or_else(|| ParakeetError::OutputNotFound("logprobs".to_string()))?;
+        let inputs = inputs![
+            "audio_signal" => TensorRef::from_array_view(audio_signal.view())?,
+            "length" => TensorRef::from_array_view(length.view())?,
+        ];
+        let outputs = self.model.run(inputs)?;
+
+        let log_probs = outputs
+            .get(&output_name)
+            .ok_or_else(|| ParakeetError::OutputNotFound(output_name.clone()))?
+            .try_extract_array::<f32>()?;
+        let encoded_lengths = match outputs.get("encoded_lengths") {
+            Some(lengths) => lengths.try_extract_array::<i64>()?.to_owned(),
+            // Without lengths, every frame counts
+            None => ArrayD::from_elem(vec![1], log_probs.shape()[1] as i64),
+        };
+
+        Ok((log_probs.to_owned(), encoded_lengths))
+    }
+}
+
+/// Best token of each of the first `frames` frames of [time, vocab] log probabilities,
+/// as (frame, token, confidence). Blanks are dropped, and repeated tokens are merged
+/// unless a blank separates them.
+fn greedy_decode(
+    log_probs: ArrayViewD<f32>,
+    frames: usize,
+    blank_idx: usize,
+) -> Vec<(usize, usize, f32)> {
+    let mut decoded = Vec::new();
+    let mut previous = blank_idx;
+    for (t, frame) in log_probs.outer_iter().take(frames).enumerate() {
+        let token = frame
+            .iter()
+            .enumerate()
+            .max_by(|(_, a), (_, b)| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal))
+            .map(|(idx, _)| idx)
+            .unwrap_or(blank_idx);
+        if token != blank_idx && token != previous {
+            // Softmax works the same on log probabilities
+            let confidence = frame.as_slice().map_or(0.0, max_probability);
+            decoded.push((t, token, confidence));
+        }
+        previous = token;
+    }
+    decoded
+}
+
+/// CTC speech recognition engine, for models that do not use a transducer decoder.
+pub struct CtcEngine {
+    model: Option<CtcModel>,
+}
+
+impl Default for CtcEngine {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CtcEngine {
+    pub fn new() -> Self {
+        Self { model: None }
+    }
+}
+
+impl TranscriptionEngine for CtcEngine {
+    type InferenceParams = CtcInferenceParams;
+    type ModelParams = CtcModelParams;
+
+    fn load_model_with_params(
+        &mut self,
+        model_path: &Path,
+        params: Self::ModelParams,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let model = CtcModel::new(model_path, &params)?;
+
+        self.model = Some(model);
+        Ok(())
+    }
+
+    fn unload_model(&mut self) {
+        self.model = None;
+    }
+
+    fn transcribe_samples(
+        &mut self,
+        samples: Vec<f32>,
+        params: Option<Self::InferenceParams>,
+    ) -> Result<TranscriptionResult, Box<dyn std::error::Error>> {
+        let model = self
+            .model
+            .as_mut()
+            .ok_or("Model not loaded. Call load_model() first.")?;
+
+        let params = params.unwrap_or_default();
+        let timestamped_result = model.transcribe_samples(samples)?;
+        let segments = convert_timestamps(&timestamped_result, params.timestamp_granularity);
+
+        Ok(TranscriptionResult {
+            confidence: timestamped_result.confidence(),
+            language: None,
+            text: timestamped_result.text,
+            segments,
+        })
+    }
+
+    fn model_files(&self) -> Vec<String> {
+        self.model
+            .as_ref()
+            .map(|model| model.onnx_files().to_vec())
+            .unwrap_or_default()
+    }
+}
+
+impl From<TimestampGranularity> for CtcInferenceParams {
+    fn from(timestamp_granularity: TimestampGranularity) -> Self {
+        Self {
+            timestamp_granularity,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BLANK: usize = 3;
+
+    /// Log probabilities where each frame puts most of its mass on one token.
+    fn frames(best: &[usize]) -> ArrayD<f32> {
+        let mut log_probs = Array2::from_elem((best.len(), 4), (0.1f32 / 3.0).ln());
+        for (t, &token) in best.iter().enumerate() {
+            log_probs[[t, token]] = 0.9f32.ln();
+        }
+        log_probs.into_dyn()
+    }
+
+    fn decoded_tokens(best: &[usize], frames_len: usize) -> Vec<(usize, usize)> {
+        greedy_decode(frames(best).view(), frames_len, BLANK)
+            .into_iter()
+            .map(|(t, token, _)| (t, token))
+            .collect()
+    }
+
+    #[test]
+    fn blanks_and_repeats_collapse() {
+        // "a a _ b _ _ b c c" -> a b b c
+        let best = [0, 0, BLANK, 1, BLANK, BLANK, 1, 2, 2];
+        assert_eq!(
+            decoded_tokens(&best, best.len()),
+            [(0, 0), (3, 1), (6, 1), (7, 2)]
+        );
+    }
+
+    #[test]
+    fn only_blanks_decode_to_nothing() {
+        assert_eq!(decoded_tokens(&[BLANK; 5], 5), []);
+    }
+
+    #[test]
+    fn frames_past_the_encoded_length_are_padding() {
+        assert_eq!(decoded_tokens(&[0, BLANK, 1, 2], 2), [(0, 0)]);
+    }
+
+    #[test]
+    fn confidence_is_the_best_token_probability() {
+        let decoded = greedy_decode(frames(&[1]).view(), 1, BLANK);
+        assert_eq!(decoded.len(), 1);
+        assert!((decoded[0].2 - 0.9).abs() < 1e-5, "{}", decoded[0].2);
+    }
+}
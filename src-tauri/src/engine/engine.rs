@@ -36,23 +36,49 @@ pub enum QuantizationType {
     Int8,
 }
 
+/// ONNX Runtime graph optimization level.
+///
+/// Higher levels make inference faster but the model slower to load,
+/// unless the optimized model is saved to disk.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub enum OptimizationLevel {
+    /// No graph optimization
+    Disable,
+    /// Redundant node removal and constant folding
+    Basic,
+    /// Basic plus complex node fusions
+    Extended,
+    /// Extended plus layout optimizations (default)
+    #[default]
+    All,
+}
+
 /// Parameters for configuring Parakeet model loading.
 ///
-/// Controls model quantization settings for balancing performance vs accuracy.
+/// Controls model quantization and ONNX Runtime session settings for
+/// balancing performance vs accuracy and resource usage.
 #[derive(Debug, Clone, Default)]
 pub struct ParakeetModelParams {
     /// The quantization type to use for the model
     pub quantization: QuantizationType,
+    /// Threads used within an operator, ONNX Runtime decides when unset
+    pub intra_threads: Option<usize>,
+    /// Threads used to run operators in parallel, ONNX Runtime decides when unset
+    pub inter_threads: Option<usize>,
+    /// Graph optimization level applied when loading the model
+    pub optimization_level: OptimizationLevel,
+    /// Directory where optimized models are saved and reused on the next load
+    pub optimized_model_dir: Option<PathBuf>,
 }
 
 impl ParakeetModelParams {
     /// Create parameters for full precision (FP32) model loading.
     ///
     /// Provides the highest accuracy but slower inference speed.
-    #[allow(dead_code)] // Will be useful for high-precision mode
     pub fn fp32() -> Self {
         Self {
             quantization: QuantizationType::FP32,
+            ..Default::default()
         }
     }
 
@@ -70,6 +96,7 @@ impl ParakeetModelParams {
     pub fn int8() -> Self {
         Self {
             quantization: QuantizationType::Int8,
+            ..Default::default()
         }
     }
 }
@@ -159,11 +186,7 @@ impl TranscriptionEngine for ParakeetEngine {
         model_path: &Path,
         params: Self::ModelParams,
     ) -> Result<(), Box<dyn std::error::Error>> {
-        let quantized = match params.quantization {
-            QuantizationType::FP32 => false,
-            QuantizationType::Int8 => true,
-        };
-        let model = ParakeetModel::new(model_path, quantized)?;
+        let model = ParakeetModel::new(model_path, &params)?;
 
         self.model = Some(model);
         self.loaded_model_path = Some(model_path.to_path_buf());
@@ -195,8 +218,56 @@ impl TranscriptionEngine for ParakeetEngine {
             convert_timestamps(&timestamped_result, parakeet_params.timestamp_granularity);
 
         Ok(TranscriptionResult {
+            confidence: timestamped_result.confidence(),
+            language: None,
             text: timestamped_result.text,
             segments,
         })
     }
+
+    /// Preprocesses and encodes the whole batch at once, then decodes each clip.
+    fn transcribe_batch(
+        &mut self,
+        batch: Vec<Vec<f32>>,
+        params: Option<Self::InferenceParams>,
+    ) -> Result<Vec<TranscriptionResult>, Box<dyn std::error::Error>> {
+        let model: &mut ParakeetModel = self
+            .model
+            .as_mut()
+            .ok_or("Model not loaded. Call load_model() first.")?;
+
+        let parakeet_params = params.unwrap_or_default();
+        let timestamped_results = model.transcribe_batch(batch)?;
+
+        Ok(timestamped_results
+            .into_iter()
+            .map(|timestamped_result| {
+                let segments = convert_timestamps(
+                    &timestamped_result,
+                    parakeet_params.timestamp_granularity.clone(),
+                );
+                TranscriptionResult {
+                    confidence: timestamped_result.confidence(),
+                    language: None,
+                    text: timestamped_result.text,
+                    segments,
+                }
+            })
+            .collect())
+    }
+
+    fn model_files(&self) -> Vec<String> {
+        self.model
+            .as_ref()
+            .map(|model| model.onnx_files().to_vec())
+            .unwrap_or_default()
+    }
+}
+
+impl From<TimestampGranularity> for ParakeetInferenceParams {
+    fn from(timestamp_granularity: TimestampGranularity) -> Self {
+        Self {
+            timestamp_granularity,
+        }
+    }
 }
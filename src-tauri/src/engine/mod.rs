@@ -1,4 +1,25 @@
+pub mod ctc;
 pub mod engine;
 pub mod model;
 pub mod timestamp;
 pub mod transcription_engine;
+
+use ctc::CtcEngine;
+use engine::{ParakeetEngine, ParakeetModelParams};
+use transcription_engine::{DynEngine, DynTranscriptionEngine};
+
+/// Engines that can be selected in the settings.
+pub const ENGINES: [&str; 2] = ["parakeet", "ctc"];
+
+/// Create an unloaded engine by name. Both engines run ONNX models, so they
+/// share the same model parameters, see `ctc::CtcModelParams`.
+pub fn create_engine(
+    name: &str,
+    params: ParakeetModelParams,
+) -> Option<Box<dyn DynTranscriptionEngine>> {
+    match name {
+        "parakeet" => Some(Box::new(DynEngine::new(ParakeetEngine::new(), params))),
+        "ctc" => Some(Box::new(DynEngine::new(CtcEngine::new(), params))),
+        _ => None,
+    }
+}
@@ -1,29 +1,72 @@
-use ndarray::{Array, Array1, Array2, Array3, ArrayD, ArrayViewD, IxDyn};
+use super::engine::{OptimizationLevel, ParakeetModelParams, QuantizationType};
+use ndarray::{Array, Array1, Array2, Array3, ArrayD, ArrayView1, ArrayViewD, IxDyn};
 use once_cell::sync::Lazy;
 use ort::execution_providers::CPUExecutionProvider;
 use ort::inputs;
-use ort::session::builder::GraphOptimizationLevel;
+use ort::session::builder::{GraphOptimizationLevel, SessionBuilder};
 use ort::session::Session;
 use ort::value::TensorRef;
 use regex::Regex;
 
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 pub type DecoderState = (Array3<f32>, Array3<f32>);
 
 const SUBSAMPLING_FACTOR: usize = 8;
-const WINDOW_SIZE: f32 = 0.01;
+pub(super) const WINDOW_SIZE: f32 = 0.01;
 const MAX_TOKENS_PER_STEP: usize = 10;
 
 static DECODE_SPACE_RE: Lazy<Result<Regex, regex::Error>> =
     Lazy::new(|| Regex::new(r"\A\s|\s\B|(\s)\b"));
 
+/// Join decoded tokens, whose leading space marks the start of a word.
+pub(super) fn tokens_to_text(tokens: &[String]) -> String {
+    match &*DECODE_SPACE_RE {
+        Ok(regex) => regex
+            .replace_all(&tokens.join(""), |caps: &regex::Captures| {
+                if caps.get(1).is_some() {
+                    " "
+                } else {
+                    ""
+                }
+            })
+            .to_string(),
+        Err(_) => tokens.join(""), // Fallback if regex failed to compile
+    }
+}
+
+/// Token ids, their frame indices and their probabilities.
+type DecodedSequence = (Vec<i32>, Vec<usize>, Vec<f32>);
+
+/// Softmax probability of the highest logit, i.e. of the token the decoder picks.
+pub(super) fn max_probability(logits: &[f32]) -> f32 {
+    let max = logits.iter().copied().fold(f32::NEG_INFINITY, f32::max);
+    let sum: f32 = logits.iter().map(|&l| (l - max).exp()).sum();
+    if sum > 0.0 {
+        1.0 / sum
+    } else {
+        0.0
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct TimestampedResult {
     pub text: String,
     pub timestamps: Vec<f32>,
     pub tokens: Vec<String>,
+    /// Probability of each token, between 0 and 1
+    pub confidences: Vec<f32>,
+}
+
+impl TimestampedResult {
+    /// Mean probability of the tokens, 0 when nothing was recognized.
+    pub fn confidence(&self) -> f32 {
+        if self.confidences.is_empty() {
+            return 0.0;
+        }
+        self.confidences.iter().sum::<f32>() / self.confidences.len() as f32
+    }
 }
 
 #[derive(thiserror::Error, Debug)]
@@ -40,6 +83,8 @@ pub enum ParakeetError {
     OutputNotFound(String),
     #[error("Failed to get tensor shape for input: {0}")]
     TensorShape(String),
+    #[error("Model file not found: {0}")]
+    ModelFileNotFound(String),
 }
 
 pub struct ParakeetModel {
@@ -49,6 +94,7 @@ pub struct ParakeetModel {
     vocab: Vec<String>,
     blank_idx: i32,
     vocab_size: usize,
+    onnx_files: Vec<String>,
 }
 
 impl Drop for ParakeetModel {
@@ -61,10 +107,17 @@ impl Drop for ParakeetModel {
 }
 
 impl ParakeetModel {
-    pub fn new<P: AsRef<Path>>(model_dir: P, quantized: bool) -> Result<Self, ParakeetError> {
-        let encoder = Self::init_session(&model_dir, "encoder-model", None, quantized)?;
-        let decoder_joint = Self::init_session(&model_dir, "decoder_joint-model", None, quantized)?;
-        let preprocessor = Self::init_session(&model_dir, "nemo128", None, false)?;
+    pub fn new<P: AsRef<Path>>(
+        model_dir: P,
+        params: &ParakeetModelParams,
+    ) -> Result<Self, ParakeetError> {
+        let quantized = params.quantization == QuantizationType::Int8;
+        let (encoder, encoder_file) =
+            Self::init_session(&model_dir, "encoder-model", params, quantized)?;
+        let (decoder_joint, decoder_joint_file) =
+            Self::init_session(&model_dir, "decoder_joint-model", params, quantized)?;
+        let (preprocessor, preprocessor_file) =
+            Self::init_session(&model_dir, "nemo128", params, false)?;
 
         let (vocab, blank_idx) = Self::load_vocab(&model_dir)?;
         let vocab_size = vocab.len();
@@ -82,17 +135,21 @@ impl ParakeetModel {
             vocab,
             blank_idx,
             vocab_size,
+            onnx_files: vec![encoder_file, decoder_joint_file, preprocessor_file],
         })
     }
 
-    fn init_session<P: AsRef<Path>>(
+    /// ONNX files the sessions were created from, which tells whether INT8 was used.
+    pub fn onnx_files(&self) -> &[String] {
+        &self.onnx_files
+    }
+
+    pub(super) fn init_session<P: AsRef<Path>>(
         model_dir: P,
         model_name: &str,
-        intra_threads: Option<usize>,
+        params: &ParakeetModelParams,
         try_quantized: bool,
-    ) -> Result<Session, ParakeetError> {
-        let providers = vec![CPUExecutionProvider::default().build()];
-
+    ) -> Result<(Session, String), ParakeetError> {
         // Try quantized version first if requested, fallback to regular version
         let model_filename = if try_quantized {
             let quantized_name = format!("{}.int8.onnx", model_name);
@@ -102,7 +159,7 @@ impl ParakeetModel {
                 quantized_name
             } else {
                 let regular_name = format!("{}.onnx", model_name);
-                log::info!(
+                log::warn!(
                     "Quantized model not found, loading regular model from {}...",
                     regular_name
                 );
@@ -114,19 +171,18 @@ impl ParakeetModel {
             regular_name
         };
 
-        let mut builder = Session::builder()?
-            .with_optimization_level(GraphOptimizationLevel::Level3)?
-            .with_execution_providers(providers)?
-            .with_memory_pattern(false)?
-            .with_parallel_execution(false)?;
-
-        if let Some(threads) = intra_threads {
-            builder = builder
-                .with_intra_threads(threads)?
-                .with_inter_threads(threads)?;
+        let model_path = model_dir.as_ref().join(&model_filename);
+        if !model_path.exists() {
+            return Err(ParakeetError::ModelFileNotFound(
+                model_path.display().to_string(),
+            ));
         }
 
-        let session = builder.commit_from_file(model_dir.as_ref().join(&model_filename))?;
+        let session = match Self::optimized_model_path(&model_path, &model_filename, params) {
+            Some(optimized_path) => Self::commit_cached(&model_path, &optimized_path, params)?,
+            None => Self::session_builder(params, params.optimization_level.clone())?
+                .commit_from_file(&model_path)?,
+        };
 
         for input in &session.inputs {
             log::info!(
@@ -137,10 +193,120 @@ impl ParakeetModel {
             );
         }
 
-        Ok(session)
+        Ok((session, model_filename))
+    }
+
+    fn session_builder(
+        params: &ParakeetModelParams,
+        optimization_level: OptimizationLevel,
+    ) -> Result<SessionBuilder, ParakeetError> {
+        let providers = vec![CPUExecutionProvider::default().build()];
+        let optimization_level = match optimization_level {
+            OptimizationLevel::Disable => GraphOptimizationLevel::Disable,
+            OptimizationLevel::Basic => GraphOptimizationLevel::Level1,
+            OptimizationLevel::Extended => GraphOptimizationLevel::Level2,
+            OptimizationLevel::All => GraphOptimizationLevel::Level3,
+        };
+        // Operators only run in parallel when more than one inter-op thread is allowed
+        let parallel = params.inter_threads.is_some_and(|threads| threads > 1);
+
+        let mut builder = Session::builder()?
+            .with_optimization_level(optimization_level)?
+            .with_execution_providers(providers)?
+            .with_memory_pattern(false)?
+            .with_parallel_execution(parallel)?;
+
+        if let Some(threads) = params.intra_threads {
+            builder = builder.with_intra_threads(threads)?;
+        }
+        if let Some(threads) = params.inter_threads {
+            builder = builder.with_inter_threads(threads)?;
+        }
+        Ok(builder)
+    }
+
+    /// Where the optimized graph of `model_filename` is cached, when caching is enabled.
+    /// The optimization level is part of the name, since the graphs differ.
+    fn optimized_model_path(
+        model_path: &Path,
+        model_filename: &str,
+        params: &ParakeetModelParams,
+    ) -> Option<PathBuf> {
+        let dir = params.optimized_model_dir.as_ref()?;
+        if params.optimization_level == OptimizationLevel::Disable {
+            return None;
+        }
+        let stem = model_filename.trim_end_matches(".onnx");
+        let level = format!("{:?}", params.optimization_level).to_lowercase();
+        let optimized_path = dir.join(format!("{}.{}.onnx", stem, level));
+        // Optimize again when the model was replaced after the graph was cached
+        let modified = |path: &Path| fs::metadata(path).and_then(|m| m.modified()).ok();
+        if modified(&optimized_path) < modified(model_path) {
+            let _ = fs::remove_file(&optimized_path);
+        }
+        Some(optimized_path)
+    }
+
+    /// Load the cached optimized graph, or optimize the model and cache it.
+    /// Caching is best effort, any failure falls back to loading the original model.
+    fn commit_cached(
+        model_path: &Path,
+        optimized_path: &Path,
+        params: &ParakeetModelParams,
+    ) -> Result<Session, ParakeetError> {
+        if optimized_path.exists() {
+            // Already optimized, running the optimizer again would only slow startup down
+            match Self::session_builder(params, OptimizationLevel::Disable)?
+                .commit_from_file(optimized_path)
+            {
+                Ok(session) => {
+                    log::info!("Loaded optimized model from {}", optimized_path.display());
+                    return Ok(session);
+                }
+                Err(e) => {
+                    log::warn!(
+                        "Discarding unusable optimized model {}: {}",
+                        optimized_path.display(),
+                        e
+                    );
+                    let _ = fs::remove_file(optimized_path);
+                }
+            }
+        }
+
+        let optimize_and_save = || -> Result<Session, ParakeetError> {
+            if let Some(dir) = optimized_path.parent() {
+                fs::create_dir_all(dir)?;
+            }
+            Ok(
+                Self::session_builder(params, params.optimization_level.clone())?
+                    .with_optimized_model_path(optimized_path)?
+                    .commit_from_file(model_path)?,
+            )
+        };
+        match optimize_and_save() {
+            Ok(session) => {
+                log::info!("Saved optimized model to {}", optimized_path.display());
+                Ok(session)
+            }
+            Err(e) => {
+                log::warn!(
+                    "Failed to save optimized model to {}: {:?}",
+                    optimized_path.display(),
+                    e
+                );
+                let _ = fs::remove_file(optimized_path);
+                Ok(
+                    Self::session_builder(params, params.optimization_level.clone())?
+                        .commit_from_file(model_path)?,
+                )
+            }
+        }
     }
 
-    fn load_vocab<P: AsRef<Path>>(model_dir: P) -> Result<(Vec<String>, i32), ParakeetError> {
+    pub(super) fn load_vocab<P: AsRef<Path>>(
+        model_dir: P,
+    ) -> Result<(Vec<String>, i32), ParakeetError> {
         let vocab_path = model_dir.as_ref().join("vocab.txt");
         let content = fs::read_to_string(vocab_path)?;
 
@@ -335,9 +501,9 @@ impl ParakeetModel {
         // Decode for each batch item
         let mut results = Vec::new();
         for (encodings, &encodings_len) in encoder_out.outer_iter().zip(encoder_out_lens.iter()) {
-            let (tokens, timestamps) =
+            let (tokens, timestamps, confidences) =
                 self.decode_sequence(&encodings.view(), encodings_len as usize)?;
-            let result = self.decode_tokens(tokens, timestamps);
+            let result = self.decode_tokens(tokens, timestamps, confidences);
             results.push(result);
         }
 
@@ -348,10 +514,11 @@ impl ParakeetModel {
         &mut self,
         encodings: &ArrayViewD<f32>, // [time_steps, 1024]
         encodings_len: usize,
-    ) -> Result<(Vec<i32>, Vec<usize>), ParakeetError> {
+    ) -> Result<DecodedSequence, ParakeetError> {
         let mut prev_state = self.create_decoder_state()?;
         let mut tokens = Vec::new();
         let mut timestamps = Vec::new();
+        let mut confidences = Vec::new();
 
         let mut t = 0;
         let mut emitted_tokens = 0;
@@ -397,6 +564,7 @@ impl ParakeetModel {
                 prev_state = new_state;
                 tokens.push(token);
                 timestamps.push(t);
+                confidences.push(max_probability(vocab_logits));
                 emitted_tokens += 1;
             }
 
@@ -407,44 +575,34 @@ impl ParakeetModel {
             }
         }
 
-        Ok((tokens, timestamps))
+        Ok((tokens, timestamps, confidences))
     }
 
-    fn decode_tokens(&self, ids: Vec<i32>, timestamps: Vec<usize>) -> TimestampedResult {
-        let tokens: Vec<String> = ids
-            .iter()
-            .filter_map(|&id| {
-                let idx = id as usize;
-                if idx < self.vocab.len() {
-                    Some(self.vocab[idx].clone())
-                } else {
-                    None
-                }
-            })
-            .collect();
-
-        let text = match &*DECODE_SPACE_RE {
-            Ok(regex) => regex
-                .replace_all(&tokens.join(""), |caps: &regex::Captures| {
-                    if caps.get(1).is_some() {
-                        " "
-                    } else {
-                        ""
-                    }
-                })
-                .to_string(),
-            Err(_) => tokens.join(""), // Fallback if regex failed to compile
-        };
+    fn decode_tokens(
+        &self,
+        ids: Vec<i32>,
+        timestamps: Vec<usize>,
+        confidences: Vec<f32>,
+    ) -> TimestampedResult {
+        let mut tokens = Vec::with_capacity(ids.len());
+        let mut float_timestamps = Vec::with_capacity(ids.len());
+        let mut token_confidences = Vec::with_capacity(ids.len());
+        for ((id, t), confidence) in ids.into_iter().zip(timestamps).zip(confidences) {
+            let Some(token) = self.vocab.get(id as usize) else {
+                continue;
+            };
+            tokens.push(token.clone());
+            float_timestamps.push(WINDOW_SIZE * SUBSAMPLING_FACTOR as f32 * t as f32);
+            token_confidences.push(confidence);
+        }
 
-        let float_timestamps: Vec<f32> = timestamps
-            .iter()
-            .map(|&t| WINDOW_SIZE * SUBSAMPLING_FACTOR as f32 * t as f32)
-            .collect();
+        let text = tokens_to_text(&tokens);
 
         TimestampedResult {
             text,
             timestamps: float_timestamps,
             tokens,
+            confidences: token_confidences,
         }
     }
 
@@ -452,17 +610,7 @@ impl ParakeetModel {
         &mut self,
         samples: Vec<f32>,
     ) -> Result<TimestampedResult, ParakeetError> {
-        let batch_size = 1;
-        let samples_len = samples.len();
-
-        // Create waveforms array [batch_size, samples_len]
-        let waveforms = Array2::from_shape_vec((batch_size, samples_len), samples)?.into_dyn();
-
-        // Create waveforms_lens array [batch_size] with the actual length
-        let waveforms_lens = Array1::from_vec(vec![samples_len as i64]).into_dyn();
-
-        // Run recognition to get detailed results
-        let results = self.recognize_batch(&waveforms.view(), &waveforms_lens.view())?;
+        let results = self.transcribe_batch(vec![samples])?;
 
         // Extract the first (and only) result
         let timestamped_result = results.into_iter().next().ok_or_else(|| {
@@ -474,4 +622,28 @@ impl ParakeetModel {
 
         Ok(timestamped_result)
     }
+
+    /// Transcribe several clips at once. They are padded with silence to the
+    /// longest one, so clips of similar length batch best.
+    pub fn transcribe_batch(
+        &mut self,
+        batch: Vec<Vec<f32>>,
+    ) -> Result<Vec<TimestampedResult>, ParakeetError> {
+        let batch_size = batch.len();
+        let max_len = batch.iter().map(Vec::len).max().unwrap_or(0);
+
+        // Create waveforms array [batch_size, max_len] and the actual lengths [batch_size]
+        let mut waveforms = Array2::<f32>::zeros((batch_size, max_len));
+        let mut lens = Vec::with_capacity(batch_size);
+        for (mut row, samples) in waveforms.outer_iter_mut().zip(&batch) {
+            row.slice_mut(ndarray::s![..samples.len()])
+                .assign(&ArrayView1::from(samples.as_slice()));
+            lens.push(samples.len() as i64);
+        }
+        let waveforms = waveforms.into_dyn();
+        let waveforms_lens = Array1::from_vec(lens).into_dyn();
+
+        // Run recognition to get detailed results
+        self.recognize_batch(&waveforms.view(), &waveforms_lens.view())
+    }
 }
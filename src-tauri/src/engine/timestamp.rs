@@ -9,6 +9,8 @@ pub struct Token {
     pub t_start: f32,
     pub t_end: f32,
     pub is_blank: bool,
+    /// Softmax probability of the token
+    pub confidence: f32,
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -17,6 +19,8 @@ pub struct Word {
     pub t_start: f32,
     pub t_end: f32,
     pub tokens: Vec<Token>,
+    /// Lowest confidence of the word's tokens
+    pub confidence: f32,
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -25,6 +29,8 @@ pub struct Segment {
     pub t_start: f32,
     pub t_end: f32,
     pub words: Vec<Word>,
+    /// Mean confidence of the segment's words
+    pub confidence: f32,
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -69,6 +75,11 @@ fn convert_to_raw_token_segments(
             start: timestamp,
             end: end_timestamp,
             text: token.clone(), // Raw token text, including spaces and subword pieces
+            confidence: timestamped_result
+                .confidences
+                .get(i)
+                .copied()
+                .unwrap_or(0.0),
         });
     }
 
@@ -118,6 +129,7 @@ fn build_utterance_from_tokens(
                     t_start: 0.0,
                     t_end: 0.0,
                     words: Vec::new(),
+                    confidence: 0.0,
                 }]
             },
         };
@@ -159,6 +171,11 @@ fn create_tokens_from_timestamped_result(timestamped_result: &TimestampedResult)
             t_start: timestamp,
             t_end,
             is_blank: token_text.trim().is_empty(),
+            confidence: timestamped_result
+                .confidences
+                .get(i)
+                .copied()
+                .unwrap_or(0.0),
         });
     }
 
@@ -210,6 +227,7 @@ fn create_word_from_tokens(tokens: &[Token]) -> Word {
             t_start: 0.0,
             t_end: 0.0,
             tokens: Vec::new(),
+            confidence: 0.0,
         };
     }
 
@@ -234,11 +252,18 @@ fn create_word_from_tokens(tokens: &[Token]) -> Word {
         .trim()
         .to_string();
 
+    // A word is only as reliable as its least certain piece
+    let confidence = tokens
+        .iter()
+        .map(|t| t.confidence)
+        .fold(f32::INFINITY, f32::min);
+
     Word {
         text,
         t_start,
         t_end,
         tokens: tokens.to_vec(),
+        confidence,
     }
 }
 
@@ -284,6 +309,7 @@ fn create_segment_from_words(words: &[Word]) -> Segment {
             t_start: 0.0,
             t_end: 0.0,
             words: Vec::new(),
+            confidence: 0.0,
         };
     }
 
@@ -299,11 +325,14 @@ fn create_segment_from_words(words: &[Word]) -> Segment {
         .collect::<Vec<_>>()
         .join(" ");
 
+    let confidence = words.iter().map(|w| w.confidence).sum::<f32>() / words.len() as f32;
+
     Segment {
         text,
         t_start,
         t_end,
         words: words.to_vec(),
+        confidence,
     }
 }
 
@@ -317,6 +346,7 @@ fn extract_word_segments(utterance: &Utterance) -> Vec<TranscriptionSegment> {
                     start: word.t_start,
                     end: word.t_end,
                     text: word.text.clone(),
+                    confidence: word.confidence,
                 });
             }
         }
@@ -334,6 +364,63 @@ fn extract_segment_segments(utterance: &Utterance) -> Vec<TranscriptionSegment>
             start: segment.t_start,
             end: segment.t_end,
             text: segment.text.clone(),
+            confidence: segment.confidence,
         })
         .collect()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn result(tokens: &[(&str, f32)]) -> TimestampedResult {
+        TimestampedResult {
+            text: tokens.iter().map(|(t, _)| *t).collect::<String>(),
+            timestamps: (0..tokens.len()).map(|i| i as f32 * 0.1).collect(),
+            tokens: tokens.iter().map(|(t, _)| t.to_string()).collect(),
+            confidences: tokens.iter().map(|(_, c)| *c).collect(),
+        }
+    }
+
+    #[test]
+    fn word_takes_its_least_confident_token() {
+        let words = convert_timestamps(
+            &result(&[("▁hel", 0.9), ("lo", 0.5), ("▁world", 0.8)]),
+            TimestampGranularity::Word,
+        );
+        let words: Vec<(&str, f32)> = words
+            .iter()
+            .map(|w| (w.text.as_str(), w.confidence))
+            .collect();
+        assert_eq!(words, [("hello", 0.5), ("world", 0.8)]);
+    }
+
+    #[test]
+    fn segment_averages_its_words() {
+        let segments = convert_timestamps(
+            &result(&[("▁yes", 0.9), ("▁no.", 0.5), ("▁ok", 0.6)]),
+            TimestampGranularity::Segment,
+        );
+        let segments: Vec<(&str, f32)> = segments
+            .iter()
+            .map(|s| (s.text.as_str(), s.confidence))
+            .collect();
+        assert_eq!(segments, [("yes no.", 0.7), ("ok", 0.6)]);
+    }
+
+    #[test]
+    fn utterance_averages_its_tokens() {
+        assert_eq!(result(&[("▁a", 1.0), ("▁b", 0.5)]).confidence(), 0.75);
+        assert_eq!(result(&[]).confidence(), 0.0);
+    }
+
+    #[test]
+    fn blank_tokens_do_not_lower_a_word() {
+        let words = convert_timestamps(
+            &result(&[("▁hi", 0.9), (" ", 0.1)]),
+            TimestampGranularity::Word,
+        );
+        assert_eq!(words.len(), 1);
+        assert_eq!(words[0].confidence, 0.9);
+    }
+}
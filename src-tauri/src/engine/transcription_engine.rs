@@ -1,5 +1,6 @@
 // Minimal transcription API types
 
+use super::engine::TimestampGranularity;
 use crate::audio::read_wav_samples;
 use std::path::Path;
 
@@ -8,12 +9,15 @@ use std::path::Path;
 /// Contains both the full transcribed text and detailed timing information
 /// for individual segments within the audio.
 #[derive(Debug)]
-#[allow(dead_code)] // segments will be useful for UI timestamps
 pub struct TranscriptionResult {
     /// The complete transcribed text from the audio
     pub text: String,
     /// Individual segments with timing information
     pub segments: Vec<TranscriptionSegment>,
+    /// Mean probability of the recognized tokens, between 0 and 1
+    pub confidence: f32,
+    /// Language code guessed from the text ("fr"), filled in by post-processing
+    pub language: Option<String>,
 }
 
 /// A single transcribed segment with timing information.
@@ -21,7 +25,6 @@ pub struct TranscriptionResult {
 /// Represents a portion of the transcribed audio with start and end timestamps
 /// and the corresponding text content.
 #[derive(Debug)]
-#[allow(dead_code)] // fields will be useful for UI timestamps
 pub struct TranscriptionSegment {
     /// Start time of the segment in seconds
     pub start: f32,
@@ -29,6 +32,8 @@ pub struct TranscriptionSegment {
     pub end: f32,
     /// The transcribed text for this segment
     pub text: String,
+    /// How sure the model is of this segment, between 0 and 1
+    pub confidence: f32,
 }
 
 /// Common interface for speech transcription engines.
@@ -134,4 +139,109 @@ pub trait TranscriptionEngine {
         let samples = read_wav_samples(wav_path)?;
         self.transcribe_samples(samples, params)
     }
+
+    /// Transcribe several clips of audio samples (16kHz, mono).
+    ///
+    /// Engines that can run a batch through the model at once override this,
+    /// by default the clips are transcribed one after the other.
+    fn transcribe_batch(
+        &mut self,
+        batch: Vec<Vec<f32>>,
+        params: Option<Self::InferenceParams>,
+    ) -> Result<Vec<TranscriptionResult>, Box<dyn std::error::Error>>
+    where
+        Self::InferenceParams: Clone,
+    {
+        batch
+            .into_iter()
+            .map(|samples| self.transcribe_samples(samples, params.clone()))
+            .collect()
+    }
+
+    /// Files the loaded model was read from, empty when none is loaded.
+    fn model_files(&self) -> Vec<String> {
+        Vec::new()
+    }
+}
+
+/// Object-safe counterpart of [`TranscriptionEngine`], so the engine can be
+/// chosen at runtime and kept as a `Box<dyn DynTranscriptionEngine>`.
+///
+/// Model parameters are given when wrapping the engine, see [`DynEngine`].
+pub trait DynTranscriptionEngine: Send {
+    /// Load a model with the parameters the engine was wrapped with.
+    fn load_model(&mut self, model_path: &Path) -> Result<(), Box<dyn std::error::Error>>;
+
+    /// Unload the currently loaded model and free associated resources.
+    fn unload_model(&mut self);
+
+    /// Transcribe audio samples (16kHz, mono) with the given timestamp granularity.
+    fn transcribe_samples(
+        &mut self,
+        samples: Vec<f32>,
+        granularity: TimestampGranularity,
+    ) -> Result<TranscriptionResult, Box<dyn std::error::Error>>;
+
+    /// Transcribe several clips with the given timestamp granularity, in order.
+    fn transcribe_batch(
+        &mut self,
+        batch: Vec<Vec<f32>>,
+        granularity: TimestampGranularity,
+    ) -> Result<Vec<TranscriptionResult>, Box<dyn std::error::Error>>;
+
+    /// Files the loaded model was read from, empty when none is loaded.
+    fn model_files(&self) -> Vec<String>;
+}
+
+/// Wraps a [`TranscriptionEngine`] along with its model parameters.
+pub struct DynEngine<E: TranscriptionEngine> {
+    engine: E,
+    model_params: E::ModelParams,
+}
+
+impl<E: TranscriptionEngine> DynEngine<E> {
+    pub fn new(engine: E, model_params: E::ModelParams) -> Self {
+        Self {
+            engine,
+            model_params,
+        }
+    }
+}
+
+impl<E> DynTranscriptionEngine for DynEngine<E>
+where
+    E: TranscriptionEngine + Send,
+    E::ModelParams: Clone + Send,
+    E::InferenceParams: From<TimestampGranularity> + Clone,
+{
+    fn load_model(&mut self, model_path: &Path) -> Result<(), Box<dyn std::error::Error>> {
+        self.engine
+            .load_model_with_params(model_path, self.model_params.clone())
+    }
+
+    fn unload_model(&mut self) {
+        self.engine.unload_model();
+    }
+
+    fn transcribe_samples(
+        &mut self,
+        samples: Vec<f32>,
+        granularity: TimestampGranularity,
+    ) -> Result<TranscriptionResult, Box<dyn std::error::Error>> {
+        self.engine
+            .transcribe_samples(samples, Some(granularity.into()))
+    }
+
+    fn transcribe_batch(
+        &mut self,
+        batch: Vec<Vec<f32>>,
+        granularity: TimestampGranularity,
+    ) -> Result<Vec<TranscriptionResult>, Box<dyn std::error::Error>> {
+        self.engine
+            .transcribe_batch(batch, Some(granularity.into()))
+    }
+
+    fn model_files(&self) -> Vec<String> {
+        self.engine.model_files()
+    }
 }
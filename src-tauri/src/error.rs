@@ -0,0 +1,80 @@
+use crate::overlay;
+use crate::settings;
+use crate::transcription;
+use serde::Serialize;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter};
+
+/// How long the overlay shows an error before hiding again.
+const ERROR_OVERLAY_DURATION: Duration = Duration::from_secs(3);
+
+/// Failures of the recording and transcription pipeline that the user should hear about.
+#[derive(Debug, thiserror::Error)]
+pub enum MurmureError {
+    #[error("No microphone found")]
+    NoInputDevice,
+    #[error("The microphone could not be configured: {0}")]
+    InputConfig(String),
+    #[error("The microphone sample format {0} is not supported")]
+    UnsupportedSampleFormat(String),
+    #[error("The microphone stream could not be started: {0}")]
+    Stream(String),
+    #[error("The recording could not be saved: {0}")]
+    RecordingFile(String),
+    #[error("No speech recognition model is available, please download one first")]
+    ModelNotAvailable(String),
+    #[error("Transcription failed: {0}")]
+    Transcription(String),
+    #[error("The dictionary could not be applied: {0}")]
+    Dictionary(String),
+    #[error("The transcription could not be saved to history: {0}")]
+    History(String),
+    #[error("The text could not be written: {0}")]
+    Output(String),
+}
+
+impl MurmureError {
+    /// Stable identifier for the frontend.
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::NoInputDevice => "no_input_device",
+            Self::InputConfig(_) => "input_config",
+            Self::UnsupportedSampleFormat(_) => "unsupported_sample_format",
+            Self::Stream(_) => "stream",
+            Self::RecordingFile(_) => "recording_file",
+            Self::ModelNotAvailable(_) => "model_not_available",
+            Self::Transcription(_) => "transcription",
+            Self::Dictionary(_) => "dictionary",
+            Self::History(_) => "history",
+            Self::Output(_) => "output",
+        }
+    }
+}
+
+/// Payload of the "error" event.
+#[derive(Serialize, Clone, Debug)]
+pub struct ErrorEvent {
+    pub code: &'static str,
+    pub message: String,
+}
+
+/// Log the error and show it to the user in the main window and the overlay.
+pub fn report(app: &AppHandle, error: &MurmureError) {
+    log::error!("{:?}", error);
+    let _ = app.emit(
+        "error",
+        ErrorEvent {
+            code: error.code(),
+            message: error.to_string(),
+        },
+    );
+
+    let s = settings::load_settings(app);
+    if s.overlay_mode.as_str() == "hidden" {
+        return;
+    }
+    overlay::show_error_overlay(app, &error.to_string());
+    if s.overlay_mode.as_str() == "recording" {
+        transcription::hide_overlay_later(app, ERROR_OVERLAY_DURATION);
+    }
+}
@@ -0,0 +1,152 @@
+/// The window that currently has keyboard focus.
+#[derive(Debug, Clone, Default)]
+pub struct FocusedWindow {
+    pub class: String,
+    pub title: String,
+}
+
+#[cfg(target_os = "linux")]
+fn active_window(conn: &impl x11rb::connection::Connection, root: u32) -> Option<u32> {
+    use x11rb::protocol::xproto::{AtomEnum, ConnectionExt};
+
+    let active_window = conn
+        .intern_atom(false, b"_NET_ACTIVE_WINDOW")
+        .ok()?
+        .reply()
+        .ok()?
+        .atom;
+    let window = conn
+        .get_property(false, root, active_window, AtomEnum::WINDOW, 0, 1)
+        .ok()?
+        .reply()
+        .ok()?
+        .value32()?
+        .next()?;
+    (window != 0).then_some(window)
+}
+
+#[cfg(target_os = "linux")]
+pub fn focused_window() -> Option<FocusedWindow> {
+    use x11rb::connection::Connection;
+    use x11rb::protocol::xproto::{AtomEnum, ConnectionExt};
+
+    let (conn, screen_num) = x11rb::connect(None).ok()?;
+    let root = conn.setup().roots.get(screen_num)?.root;
+    let atom = |name: &[u8]| -> Option<u32> {
+        Some(conn.intern_atom(false, name).ok()?.reply().ok()?.atom)
+    };
+
+    let window = active_window(&conn, root)?;
+
+    // WM_CLASS holds "instance\0class\0"
+    let class = conn
+        .get_property(false, window, AtomEnum::WM_CLASS, AtomEnum::STRING, 0, 1024)
+        .ok()?
+        .reply()
+        .ok()?
+        .value;
+    let class = class
+        .split(|&b| b == 0)
+        .filter(|part| !part.is_empty())
+        .map(|part| String::from_utf8_lossy(part).to_string())
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    let title = atom(b"_NET_WM_NAME")
+        .zip(atom(b"UTF8_STRING"))
+        .and_then(|(net_wm_name, utf8)| {
+            conn.get_property(false, window, net_wm_name, utf8, 0, 1024)
+                .ok()?
+                .reply()
+                .ok()
+        })
+        .map(|reply| reply.value)
+        .filter(|value| !value.is_empty())
+        .or_else(|| {
+            conn.get_property(false, window, AtomEnum::WM_NAME, AtomEnum::STRING, 0, 1024)
+                .ok()?
+                .reply()
+                .ok()
+                .map(|reply| reply.value)
+        })
+        .map(|value| String::from_utf8_lossy(&value).to_string())
+        .unwrap_or_default();
+
+    Some(FocusedWindow { class, title })
+}
+
+#[cfg(target_os = "windows")]
+pub fn focused_window() -> Option<FocusedWindow> {
+    use windows_sys::Win32::UI::WindowsAndMessaging::{
+        GetClassNameW, GetForegroundWindow, GetWindowTextW,
+    };
+
+    let hwnd = unsafe { GetForegroundWindow() };
+    if hwnd == 0 {
+        return None;
+    }
+
+    let mut buffer = [0u16; 512];
+    let len = unsafe { GetClassNameW(hwnd, buffer.as_mut_ptr(), buffer.len() as i32) };
+    let class = String::from_utf16_lossy(&buffer[..len.max(0) as usize]);
+
+    let len = unsafe { GetWindowTextW(hwnd, buffer.as_mut_ptr(), buffer.len() as i32) };
+    let title = String::from_utf16_lossy(&buffer[..len.max(0) as usize]);
+
+    Some(FocusedWindow { class, title })
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "windows")))]
+pub fn focused_window() -> Option<FocusedWindow> {
+    None
+}
+
+/// Center of the focused window, in physical screen coordinates.
+#[cfg(target_os = "linux")]
+pub fn focused_window_center() -> Option<(f64, f64)> {
+    use x11rb::connection::Connection;
+    use x11rb::protocol::xproto::ConnectionExt;
+
+    let (conn, screen_num) = x11rb::connect(None).ok()?;
+    let root = conn.setup().roots.get(screen_num)?.root;
+    let window = active_window(&conn, root)?;
+    let geometry = conn.get_geometry(window).ok()?.reply().ok()?;
+    let origin = conn
+        .translate_coordinates(window, root, 0, 0)
+        .ok()?
+        .reply()
+        .ok()?;
+    Some((
+        origin.dst_x as f64 + geometry.width as f64 / 2.0,
+        origin.dst_y as f64 + geometry.height as f64 / 2.0,
+    ))
+}
+
+#[cfg(target_os = "windows")]
+pub fn focused_window_center() -> Option<(f64, f64)> {
+    use windows_sys::Win32::Foundation::RECT;
+    use windows_sys::Win32::UI::WindowsAndMessaging::{GetForegroundWindow, GetWindowRect};
+
+    let hwnd = unsafe { GetForegroundWindow() };
+    if hwnd == 0 {
+        return None;
+    }
+    let mut rect = RECT {
+        left: 0,
+        top: 0,
+        right: 0,
+        bottom: 0,
+    };
+    if unsafe { GetWindowRect(hwnd, &mut rect) } == 0 {
+        return None;
+    }
+    Some((
+        (rect.left + rect.right) as f64 / 2.0,
+        (rect.top + rect.bottom) as f64 / 2.0,
+    ))
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "windows")))]
+pub fn focused_window_center() -> Option<(f64, f64)> {
+    None
+}
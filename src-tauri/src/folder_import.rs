@@ -0,0 +1,90 @@
+use crate::audio;
+use anyhow::{Context, Result};
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Emitter};
+
+/// Result for one file of the folder, with either its text or an error.
+#[derive(Serialize, Clone, Debug)]
+pub struct FileTranscription {
+    pub file: String,
+    pub text: Option<String>,
+    pub error: Option<String>,
+}
+
+/// Payload of the "folder-import-progress" event.
+#[derive(Serialize, Clone, Debug)]
+pub struct FolderImportProgress {
+    pub done: usize,
+    pub total: usize,
+}
+
+/// WAV files directly inside `folder`, sorted by name.
+fn wav_files(folder: &Path) -> Result<Vec<PathBuf>> {
+    let mut files: Vec<PathBuf> = fs::read_dir(folder)
+        .with_context(|| format!("Failed to read {}", folder.display()))?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| {
+            path.is_file()
+                && path
+                    .extension()
+                    .is_some_and(|ext| ext.eq_ignore_ascii_case("wav"))
+        })
+        .collect();
+    files.sort();
+    Ok(files)
+}
+
+/// Transcribe every WAV file of a folder, e.g. an archive of voice memos.
+/// With `write_text_files`, each text is also saved next to its recording as a .txt file.
+pub fn transcribe_folder(
+    app: &AppHandle,
+    folder: &Path,
+    write_text_files: bool,
+) -> Result<Vec<FileTranscription>> {
+    let files = wav_files(folder)?;
+    log::info!(
+        "Transcribing {} files from {}",
+        files.len(),
+        folder.display()
+    );
+    let total = files.len();
+    let results = audio::transcribe_files(app, &files, |done| {
+        let _ = app.emit(
+            "folder-import-progress",
+            FolderImportProgress { done, total },
+        );
+    })?;
+
+    Ok(files
+        .iter()
+        .zip(results)
+        .map(|(path, result)| {
+            let file = path
+                .file_name()
+                .map(|name| name.to_string_lossy().to_string())
+                .unwrap_or_default();
+            let text = match result {
+                Ok(result) => audio::post_process(app, result).text,
+                Err(error) => {
+                    log::warn!("Failed to transcribe {}: {}", path.display(), error);
+                    return FileTranscription {
+                        file,
+                        text: None,
+                        error: Some(error),
+                    };
+                }
+            };
+            let error = write_text_files
+                .then(|| fs::write(path.with_extension("txt"), &text).err())
+                .flatten()
+                .map(|e| format!("Failed to save the text file: {}", e));
+            FileTranscription {
+                file,
+                text: Some(text),
+                error,
+            }
+        })
+        .collect())
+}
@@ -11,6 +11,10 @@ pub struct HistoryEntry {
     pub id: u64,
     pub timestamp: i64,
     pub text: String,
+    #[serde(default)]
+    pub confidence: Option<f32>, // Mean token probability, missing in older entries
+    #[serde(default)]
+    pub language: Option<String>, // Detected language code, when it could be told
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -53,7 +57,12 @@ fn write_history(app: &AppHandle, data: &HistoryData) -> Result<()> {
     Ok(())
 }
 
-pub fn add_transcription(app: &AppHandle, text: String) -> Result<()> {
+pub fn add_transcription(
+    app: &AppHandle,
+    text: String,
+    confidence: f32,
+    language: Option<String>,
+) -> Result<()> {
     let mut data = read_history(app)?;
 
     let timestamp = std::time::SystemTime::now()
@@ -64,6 +73,8 @@ pub fn add_transcription(app: &AppHandle, text: String) -> Result<()> {
         id: data.next_id,
         timestamp,
         text,
+        confidence: Some(confidence),
+        language,
     };
 
     data.entries.insert(0, entry);
@@ -14,7 +14,7 @@ pub fn spawn_http_api_thread(app_handle: AppHandle, port: u16, state: HttpApiSta
                     state.clone(),
                 )) {
                     let error_msg = e.to_string();
-                    eprintln!("HTTP API error: {}", error_msg);
+                    log::error!("HTTP API error: {}", error_msg);
 
                     let is_port_conflict = error_msg.to_lowercase().contains("address already in use")
                         || error_msg.contains("address in use")
@@ -44,7 +44,7 @@ pub fn spawn_http_api_thread(app_handle: AppHandle, port: u16, state: HttpApiSta
                 }
             }
             Err(e) => {
-                eprintln!("Failed to create async runtime for HTTP API: {}", e);
+                log::error!("Failed to create async runtime for HTTP API: {}", e);
                 let msg = format!("Failed to create async runtime for HTTP API: {}", e);
                 let _ = app_handle
                     .dialog()
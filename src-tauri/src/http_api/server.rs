@@ -1,5 +1,6 @@
 use crate::audio;
-use crate::dictionary::{fix_transcription_with_dictionary, get_cc_rules_path, Dictionary};
+use crate::engine::transcription_engine::TranscriptionResult;
+use crate::error::MurmureError;
 use anyhow::Result;
 use axum::{
     extract::{DefaultBodyLimit, Multipart},
@@ -11,11 +12,63 @@ use axum::{
 use serde::{Deserialize, Serialize};
 use std::net::SocketAddr;
 use std::sync::Arc;
-use tauri::Manager;
+use tauri::Emitter;
 
 #[derive(Serialize, Deserialize)]
 pub struct TranscriptionResponse {
     pub text: String,
+    /// Word timings from the model, after filler removal
+    pub words: Vec<WordTimestamp>,
+    /// Mean probability of the recognized tokens, between 0 and 1
+    pub confidence: f32,
+    /// Language code guessed from the text, null when it could not be told
+    pub language: Option<String>,
+}
+
+impl From<TranscriptionResult> for TranscriptionResponse {
+    fn from(result: TranscriptionResult) -> Self {
+        let words = result
+            .segments
+            .iter()
+            .map(|w| WordTimestamp {
+                text: w.text.clone(),
+                start: w.start,
+                end: w.end,
+                confidence: w.confidence,
+            })
+            .collect();
+        Self {
+            text: result.text,
+            words,
+            confidence: result.confidence,
+            language: result.language,
+        }
+    }
+}
+
+/// One file of a batch, with either its transcription or an error.
+#[derive(Serialize, Deserialize)]
+pub struct BatchTranscriptionItem {
+    /// File name of the uploaded part
+    pub file: String,
+    #[serde(flatten)]
+    pub transcription: Option<TranscriptionResponse>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct BatchTranscriptionResponse {
+    /// In the order of the `audio` fields
+    pub results: Vec<BatchTranscriptionItem>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct WordTimestamp {
+    pub text: String,
+    pub start: f32,
+    pub end: f32,
+    pub confidence: f32,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -32,27 +85,32 @@ pub async fn start_http_api(
 
     let router = Router::new()
         .route("/api/transcribe", post(transcribe_handler))
+        .route("/api/transcribe/batch", post(transcribe_batch_handler))
         .with_state(app.clone())
         .layer(DefaultBodyLimit::max(100_000_000));
 
     let addr = SocketAddr::from(([127, 0, 0, 1], port));
     let listener = tokio::net::TcpListener::bind(&addr).await?;
 
-    println!("HTTP API listening on http://{}", addr);
+    log::info!("HTTP API listening on http://{}", addr);
 
     let (shutdown_tx, shutdown_rx) = tokio::sync::oneshot::channel::<()>();
     api_state.set_shutdown_sender(shutdown_tx);
+    let _ = app.emit("http-api-changed", true);
 
     let server = axum::serve(listener, router);
 
     tokio::select! {
         _ = server => {
-            println!("HTTP API server ended normally");
+            log::info!("HTTP API server ended normally");
         }
         _ = shutdown_rx => {
-            println!("HTTP API server shutdown signal received");
+            log::info!("HTTP API server shutdown signal received");
         }
     }
+    // No longer running either way
+    api_state.stop();
+    let _ = app.emit("http-api-changed", false);
 
     Ok(())
 }
@@ -93,20 +151,9 @@ async fn transcribe_handler(
 
                     let result = match audio::preload_engine(&app) {
                         Ok(_) => match audio::transcribe_audio(&temp_path) {
-                            Ok(raw_text) => {
-                                let text = match get_cc_rules_path(&app) {
-                                    Ok(cc_rules_path) => {
-                                        let dictionary = app.state::<Dictionary>().get();
-                                        fix_transcription_with_dictionary(
-                                            raw_text,
-                                            dictionary,
-                                            cc_rules_path,
-                                        )
-                                    }
-                                    Err(_) => raw_text,
-                                };
-
-                                Ok(text)
+                            Ok(result) => {
+                                let result = audio::post_process(&app, result);
+                                Ok(TranscriptionResponse::from(result))
                             }
                             Err(e) => Err(format!("Transcription failed: {}", e)),
                         },
@@ -116,9 +163,7 @@ async fn transcribe_handler(
                     let _ = std::fs::remove_file(&temp_path);
 
                     return match result {
-                        Ok(text) => {
-                            (StatusCode::OK, Json(TranscriptionResponse { text })).into_response()
-                        }
+                        Ok(response) => (StatusCode::OK, Json(response)).into_response(),
                         Err(e) => (
                             StatusCode::INTERNAL_SERVER_ERROR,
                             Json(ErrorResponse { error: e }),
@@ -149,4 +194,112 @@ async fn transcribe_handler(
         .into_response()
 }
 
+fn error_response(status: StatusCode, error: String) -> axum::response::Response {
+    (status, Json(ErrorResponse { error })).into_response()
+}
 
+/// Transcribe every `audio` field of the request, in batches.
+async fn transcribe_batch_handler(
+    axum::extract::State(app): axum::extract::State<Arc<tauri::AppHandle>>,
+    mut multipart: Multipart,
+) -> impl IntoResponse {
+    let mut uploads = Vec::new();
+    loop {
+        match multipart.next_field().await {
+            Ok(Some(field)) => {
+                if field.name() != Some("audio") {
+                    continue;
+                }
+                let file = field
+                    .file_name()
+                    .map(str::to_string)
+                    .unwrap_or_else(|| format!("audio-{}", uploads.len() + 1));
+                match field.bytes().await {
+                    Ok(bytes) => uploads.push((file, bytes)),
+                    Err(e) => {
+                        return error_response(
+                            StatusCode::BAD_REQUEST,
+                            format!("Failed to read audio file '{}': {}", file, e),
+                        )
+                    }
+                }
+            }
+            Ok(None) => break,
+            Err(e) => {
+                return error_response(
+                    StatusCode::BAD_REQUEST,
+                    format!("Failed to parse multipart: {}", e),
+                )
+            }
+        }
+    }
+    if uploads.is_empty() {
+        return error_response(
+            StatusCode::BAD_REQUEST,
+            "No 'audio' field in multipart request".to_string(),
+        );
+    }
+
+    let mut paths = Vec::new();
+    for (_, bytes) in &uploads {
+        let path = std::env::temp_dir().join(format!("murmure-{}.wav", uuid::Uuid::new_v4()));
+        let written = std::fs::write(&path, bytes);
+        paths.push(path);
+        if let Err(e) = written {
+            for path in &paths {
+                let _ = std::fs::remove_file(path);
+            }
+            return error_response(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Failed to write audio file: {}", e),
+            );
+        }
+    }
+
+    // Blocking and long for large batches, it must not hold up the other requests
+    let worker_app = app.clone();
+    let transcribed = tokio::task::spawn_blocking(move || {
+        let transcribed = audio::transcribe_files(&worker_app, &paths, |_| {});
+        for path in &paths {
+            let _ = std::fs::remove_file(path);
+        }
+        transcribed.map(|results| {
+            results
+                .into_iter()
+                .map(|result| result.map(|result| audio::post_process(&worker_app, result)))
+                .collect::<Vec<_>>()
+        })
+    })
+    .await;
+
+    match transcribed {
+        Ok(Ok(results)) => {
+            let results = uploads
+                .into_iter()
+                .zip(results)
+                .map(|((file, _), result)| match result {
+                    Ok(result) => BatchTranscriptionItem {
+                        file,
+                        transcription: Some(result.into()),
+                        error: None,
+                    },
+                    Err(error) => BatchTranscriptionItem {
+                        file,
+                        transcription: None,
+                        error: Some(error),
+                    },
+                })
+                .collect();
+            (StatusCode::OK, Json(BatchTranscriptionResponse { results })).into_response()
+        }
+        Ok(Err(MurmureError::ModelNotAvailable(e))) => error_response(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Model not available: {}", e),
+        ),
+        Ok(Err(e)) => error_response(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()),
+        Err(e) => error_response(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Transcription task failed: {}", e),
+        ),
+    }
+}
@@ -18,6 +18,10 @@ impl HttpApiState {
         *guard = Some(tx);
     }
 
+    pub fn is_running(&self) -> bool {
+        self.shutdown_tx.lock().unwrap().is_some()
+    }
+
     pub fn stop(&self) {
         let mut guard = self.shutdown_tx.lock().unwrap();
         if let Some(tx) = guard.take() {
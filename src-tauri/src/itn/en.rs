@@ -0,0 +1,238 @@
+use super::{Grammar, NumberWord};
+
+pub static GRAMMAR: Grammar = Grammar {
+    numbers: &[
+        ("zero", NumberWord::Unit(0)),
+        ("oh", NumberWord::Unit(0)),
+        ("one", NumberWord::Unit(1)),
+        ("two", NumberWord::Unit(2)),
+        ("three", NumberWord::Unit(3)),
+        ("four", NumberWord::Unit(4)),
+        ("five", NumberWord::Unit(5)),
+        ("six", NumberWord::Unit(6)),
+        ("seven", NumberWord::Unit(7)),
+        ("eight", NumberWord::Unit(8)),
+        ("nine", NumberWord::Unit(9)),
+        ("ten", NumberWord::Teen(10)),
+        ("eleven", NumberWord::Teen(11)),
+        ("twelve", NumberWord::Teen(12)),
+        ("thirteen", NumberWord::Teen(13)),
+        ("fourteen", NumberWord::Teen(14)),
+        ("fifteen", NumberWord::Teen(15)),
+        ("sixteen", NumberWord::Teen(16)),
+        ("seventeen", NumberWord::Teen(17)),
+        ("eighteen", NumberWord::Teen(18)),
+        ("nineteen", NumberWord::Teen(19)),
+        ("twenty", NumberWord::Tens(20)),
+        ("thirty", NumberWord::Tens(30)),
+        ("forty", NumberWord::Tens(40)),
+        ("fifty", NumberWord::Tens(50)),
+        ("sixty", NumberWord::Tens(60)),
+        ("seventy", NumberWord::Tens(70)),
+        ("eighty", NumberWord::Tens(80)),
+        ("ninety", NumberWord::Tens(90)),
+        ("hundred", NumberWord::Hundred),
+        ("thousand", NumberWord::Scale(1_000)),
+        ("million", NumberWord::Scale(1_000_000)),
+        ("billion", NumberWord::Scale(1_000_000_000)),
+        ("and", NumberWord::Connector),
+    ],
+    tens_taking_teens: &[],
+    bare_hundred: false,
+    bare_scales: &[],
+    decimal_words: &["point"],
+    decimal_separator: '.',
+    units: &[
+        ("percent", "%"),
+        ("per cent", "%"),
+        ("degrees celsius", "°C"),
+        ("degrees fahrenheit", "°F"),
+        ("degrees", "°"),
+        ("kilometers per hour", " km/h"),
+        ("kilometres per hour", " km/h"),
+        ("miles per hour", " mph"),
+        ("kilometers", " km"),
+        ("kilometer", " km"),
+        ("kilometres", " km"),
+        ("kilometre", " km"),
+        ("meters", " m"),
+        ("meter", " m"),
+        ("metres", " m"),
+        ("metre", " m"),
+        ("centimeters", " cm"),
+        ("centimeter", " cm"),
+        ("centimetres", " cm"),
+        ("centimetre", " cm"),
+        ("millimeters", " mm"),
+        ("millimeter", " mm"),
+        ("millimetres", " mm"),
+        ("millimetre", " mm"),
+        ("kilograms", " kg"),
+        ("kilogram", " kg"),
+        ("kilos", " kg"),
+        ("grams", " g"),
+        ("gram", " g"),
+        ("liters", " L"),
+        ("liter", " L"),
+        ("litres", " L"),
+        ("litre", " L"),
+        ("milliliters", " mL"),
+        ("millilitres", " mL"),
+        ("gigabytes", " GB"),
+        ("megabytes", " MB"),
+        ("kilobytes", " KB"),
+    ],
+    currencies: &[
+        ("dollars", "$"),
+        ("dollar", "$"),
+        ("euros", "€"),
+        ("euro", "€"),
+        ("pounds", "£"),
+        ("pound", "£"),
+    ],
+    currency_prefix: true,
+    ordinals: &[
+        ("first", 1),
+        ("second", 2),
+        ("third", 3),
+        ("fourth", 4),
+        ("fifth", 5),
+        ("sixth", 6),
+        ("seventh", 7),
+        ("eighth", 8),
+        ("ninth", 9),
+        ("tenth", 10),
+        ("eleventh", 11),
+        ("twelfth", 12),
+        ("thirteenth", 13),
+        ("fourteenth", 14),
+        ("fifteenth", 15),
+        ("sixteenth", 16),
+        ("seventeenth", 17),
+        ("eighteenth", 18),
+        ("nineteenth", 19),
+        ("twentieth", 20),
+        ("thirtieth", 30),
+    ],
+    ordinal_suffixes: &["st", "nd", "rd", "th"],
+    months: &[
+        ("january", "January"),
+        ("february", "February"),
+        ("march", "March"),
+        ("april", "April"),
+        ("may", "May"),
+        ("june", "June"),
+        ("july", "July"),
+        ("august", "August"),
+        ("september", "September"),
+        ("october", "October"),
+        ("november", "November"),
+        ("december", "December"),
+    ],
+    date_articles: &["the"],
+    date_links: &["of"],
+    cardinal_days: false,
+    month_first_dates: true,
+    paired_years: true,
+    format_date,
+};
+
+fn format_date(month: &str, day: u64, year: Option<u64>) -> String {
+    match year {
+        Some(year) => format!("{} {}, {}", month, day, year),
+        None => format!("{} {}", month, day),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::itn::{normalize, ItnLanguage};
+
+    fn check(cases: &[(&str, &str)]) {
+        for (input, expected) in cases {
+            assert_eq!(
+                normalize(input, ItnLanguage::English),
+                *expected,
+                "input: {:?}",
+                input
+            );
+        }
+    }
+
+    #[test]
+    fn cardinals() {
+        check(&[
+            ("twenty three", "23"),
+            ("thirty three", "33"),
+            ("one hundred and five", "105"),
+            ("one thousand", "1000"),
+            ("two thousand twenty five", "2025"),
+            ("nine hundred ninety nine thousand", "999000"),
+            ("one million two hundred thousand", "1200000"),
+            ("Twenty three people came.", "23 people came."),
+            ("I said twenty, then thirty", "I said 20, then 30"),
+        ]);
+    }
+
+    #[test]
+    fn ordinals() {
+        check(&[
+            ("the second of april", "April 2"),
+            ("the twenty first of june", "June 21"),
+            ("December 25th", "December 25"),
+        ]);
+    }
+
+    #[test]
+    fn decimals() {
+        check(&[
+            ("three point one four", "3.14"),
+            ("zero point five", "0.5"),
+            ("twenty three point five percent", "23.5%"),
+        ]);
+    }
+
+    #[test]
+    fn dates() {
+        check(&[
+            ("the third of march", "March 3"),
+            ("March third", "March 3"),
+            ("May fifth", "May 5"),
+            (
+                "the twenty first of june twenty twenty five",
+                "June 21, 2025",
+            ),
+            (
+                "the thirty first of december nineteen ninety nine",
+                "December 31, 1999",
+            ),
+        ]);
+    }
+
+    #[test]
+    fn units_and_currencies() {
+        check(&[
+            ("twelve percent", "12%"),
+            ("ten kilometers", "10 km"),
+            ("one point two five meters", "1.25 m"),
+            ("three hundred grams", "300 g"),
+            ("five degrees", "5°"),
+            ("fifty dollars", "$50"),
+            ("forty two euros", "€42"),
+        ]);
+    }
+
+    #[test]
+    fn stays_in_words() {
+        check(&[
+            ("seven", "seven"),
+            ("zero", "zero"),
+            ("one of them", "one of them"),
+            ("I have two cats", "I have two cats"),
+            ("the first time", "the first time"),
+            ("oh no", "oh no"),
+            ("a hundred", "a hundred"),
+            ("you may fifth", "you may fifth"),
+        ]);
+    }
+}
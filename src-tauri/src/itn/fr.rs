@@ -0,0 +1,213 @@
+use super::{Grammar, NumberWord};
+
+pub static GRAMMAR: Grammar = Grammar {
+    numbers: &[
+        ("zéro", NumberWord::Unit(0)),
+        ("zero", NumberWord::Unit(0)),
+        ("un", NumberWord::Unit(1)),
+        ("une", NumberWord::Unit(1)),
+        ("deux", NumberWord::Unit(2)),
+        ("trois", NumberWord::Unit(3)),
+        ("quatre", NumberWord::Unit(4)),
+        ("cinq", NumberWord::Unit(5)),
+        ("six", NumberWord::Unit(6)),
+        ("sept", NumberWord::Unit(7)),
+        ("huit", NumberWord::Unit(8)),
+        ("neuf", NumberWord::Unit(9)),
+        ("dix", NumberWord::Teen(10)),
+        ("onze", NumberWord::Teen(11)),
+        ("douze", NumberWord::Teen(12)),
+        ("treize", NumberWord::Teen(13)),
+        ("quatorze", NumberWord::Teen(14)),
+        ("quinze", NumberWord::Teen(15)),
+        ("seize", NumberWord::Teen(16)),
+        ("dix sept", NumberWord::Teen(17)),
+        ("dix huit", NumberWord::Teen(18)),
+        ("dix neuf", NumberWord::Teen(19)),
+        ("vingt", NumberWord::Tens(20)),
+        ("trente", NumberWord::Tens(30)),
+        ("quarante", NumberWord::Tens(40)),
+        ("cinquante", NumberWord::Tens(50)),
+        ("soixante", NumberWord::Tens(60)),
+        ("septante", NumberWord::Tens(70)),
+        ("quatre vingt", NumberWord::Tens(80)),
+        ("quatre vingts", NumberWord::Tens(80)),
+        ("huitante", NumberWord::Tens(80)),
+        ("octante", NumberWord::Tens(80)),
+        ("nonante", NumberWord::Tens(90)),
+        ("cent", NumberWord::Hundred),
+        ("cents", NumberWord::Hundred),
+        ("mille", NumberWord::Scale(1_000)),
+        ("million", NumberWord::Scale(1_000_000)),
+        ("millions", NumberWord::Scale(1_000_000)),
+        ("milliard", NumberWord::Scale(1_000_000_000)),
+        ("milliards", NumberWord::Scale(1_000_000_000)),
+        ("et", NumberWord::Connector),
+    ],
+    tens_taking_teens: &[60, 80],
+    bare_hundred: true,
+    bare_scales: &[1_000],
+    decimal_words: &["virgule"],
+    decimal_separator: ',',
+    units: &[
+        ("pour cent", " %"),
+        ("pourcent", " %"),
+        ("pourcents", " %"),
+        ("degrés celsius", " °C"),
+        ("degrés", "°"),
+        ("degré", "°"),
+        ("kilomètres heure", " km/h"),
+        ("kilomètres par heure", " km/h"),
+        ("kilomètres", " km"),
+        ("kilomètre", " km"),
+        ("mètres", " m"),
+        ("mètre", " m"),
+        ("centimètres", " cm"),
+        ("centimètre", " cm"),
+        ("millimètres", " mm"),
+        ("millimètre", " mm"),
+        ("kilogrammes", " kg"),
+        ("kilogramme", " kg"),
+        ("kilos", " kg"),
+        ("kilo", " kg"),
+        ("grammes", " g"),
+        ("gramme", " g"),
+        ("litres", " l"),
+        ("litre", " l"),
+        ("millilitres", " ml"),
+        ("gigaoctets", " Go"),
+        ("mégaoctets", " Mo"),
+    ],
+    currencies: &[
+        ("euros", "€"),
+        ("euro", "€"),
+        ("dollars", "$"),
+        ("dollar", "$"),
+        ("livres sterling", "£"),
+        ("francs suisses", "CHF"),
+    ],
+    currency_prefix: false,
+    ordinals: &[("premier", 1), ("première", 1)],
+    ordinal_suffixes: &["er", "ère"],
+    months: &[
+        ("janvier", "janvier"),
+        ("février", "février"),
+        ("fevrier", "février"),
+        ("mars", "mars"),
+        ("avril", "avril"),
+        ("mai", "mai"),
+        ("juin", "juin"),
+        ("juillet", "juillet"),
+        ("août", "août"),
+        ("aout", "août"),
+        ("septembre", "septembre"),
+        ("octobre", "octobre"),
+        ("novembre", "novembre"),
+        ("décembre", "décembre"),
+        ("decembre", "décembre"),
+    ],
+    date_articles: &[],
+    date_links: &[],
+    cardinal_days: true,
+    month_first_dates: false,
+    paired_years: false,
+    format_date,
+};
+
+fn format_date(month: &str, day: u64, year: Option<u64>) -> String {
+    let day = if day == 1 {
+        "1er".to_string()
+    } else {
+        day.to_string()
+    };
+    match year {
+        Some(year) => format!("{} {} {}", day, month, year),
+        None => format!("{} {}", day, month),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::itn::{normalize, ItnLanguage};
+
+    fn check(cases: &[(&str, &str)]) {
+        for (input, expected) in cases {
+            assert_eq!(
+                normalize(input, ItnLanguage::French),
+                *expected,
+                "input: {:?}",
+                input
+            );
+        }
+    }
+
+    #[test]
+    fn cardinals() {
+        check(&[
+            ("vingt trois", "23"),
+            ("soixante et onze", "71"),
+            ("soixante-dix", "70"),
+            ("quatre vingt", "80"),
+            ("quatre-vingt-onze", "91"),
+            ("quatre-vingt-dix-sept", "97"),
+            ("cent", "100"),
+            ("cent vingt", "120"),
+            ("mille", "1000"),
+            ("mille deux cents", "1200"),
+            ("deux mille vingt cinq", "2025"),
+            ("il a dit vingt, puis trente", "il a dit 20, puis 30"),
+        ]);
+    }
+
+    #[test]
+    fn ordinals() {
+        check(&[
+            ("le premier janvier", "le 1er janvier"),
+            ("le deux avril", "le 2 avril"),
+        ]);
+    }
+
+    #[test]
+    fn decimals() {
+        check(&[
+            ("trois virgule un quatre", "3,14"),
+            ("zéro virgule cinq", "0,5"),
+            ("vingt trois virgule cinq pour cent", "23,5 %"),
+        ]);
+    }
+
+    #[test]
+    fn dates() {
+        check(&[
+            ("trois mars", "3 mars"),
+            ("le trois mars deux mille vingt", "le 3 mars 2020"),
+            (
+                "le trente et un décembre mille neuf cent quatre-vingt-dix-neuf",
+                "le 31 décembre 1999",
+            ),
+        ]);
+    }
+
+    #[test]
+    fn units_and_currencies() {
+        check(&[
+            ("douze pour cent", "12 %"),
+            ("dix kilomètres", "10 km"),
+            ("un virgule deux cinq mètres", "1,25 m"),
+            ("trois cents grammes", "300 g"),
+            ("cinquante euros", "50 €"),
+        ]);
+    }
+
+    #[test]
+    fn stays_in_words() {
+        check(&[
+            ("sept", "sept"),
+            ("deux", "deux"),
+            ("un de ces jours", "un de ces jours"),
+            ("j'ai deux chats", "j'ai deux chats"),
+            ("le premier", "le premier"),
+            ("deuxième", "deuxième"),
+        ]);
+    }
+}
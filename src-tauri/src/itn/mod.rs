@@ -0,0 +1,543 @@
+// Inverse text normalization (ITN)
+//
+// Turns spoken forms produced by the model into their written forms:
+// "twenty three point five percent" -> "23.5%", "the third of march" -> "March 3".
+// Each language provides its own grammar tables, the matching logic is shared.
+
+mod en;
+mod fr;
+
+use crate::settings::AppSettings;
+
+/// Kind of a spoken number word, used to validate how words combine.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum NumberWord {
+    /// 0 to 9
+    Unit(u64),
+    /// 10 to 19
+    Teen(u64),
+    /// 20, 30, ... 90
+    Tens(u64),
+    Hundred,
+    /// thousand, million, billion
+    Scale(u64),
+    /// "and" / "et" bridging two number words
+    Connector,
+}
+
+/// Language specific tables driving the normalization.
+pub struct Grammar {
+    /// Number words, keys may span several words ("quatre vingt")
+    pub numbers: &'static [(&'static str, NumberWord)],
+    /// Tens that can be followed by a teen ("soixante-dix", "quatre-vingt-onze")
+    pub tens_taking_teens: &'static [u64],
+    /// Whether "hundred" alone means 100 ("cent")
+    pub bare_hundred: bool,
+    /// Scales that can stand alone without a multiplier ("mille")
+    pub bare_scales: &'static [u64],
+    pub decimal_words: &'static [&'static str],
+    pub decimal_separator: char,
+    /// Units written after the number, the symbol includes its leading spacing
+    pub units: &'static [(&'static str, &'static str)],
+    pub currencies: &'static [(&'static str, &'static str)],
+    /// "$23" when true, "23 €" when false
+    pub currency_prefix: bool,
+    pub ordinals: &'static [(&'static str, u64)],
+    /// Suffixes of digit ordinals ("3rd", "1er")
+    pub ordinal_suffixes: &'static [&'static str],
+    /// Lowercase spoken month -> written month
+    pub months: &'static [(&'static str, &'static str)],
+    /// Articles dropped in front of a day ("the third of march")
+    pub date_articles: &'static [&'static str],
+    /// Words linking a day to its month ("of"), required when non-empty
+    pub date_links: &'static [&'static str],
+    /// Whether a plain cardinal can be a day ("trois mars")
+    pub cardinal_days: bool,
+    /// Whether "march third" is recognized in addition to "third of march"
+    pub month_first_dates: bool,
+    /// Whether years can be spoken as two pairs ("twenty twenty five")
+    pub paired_years: bool,
+    pub format_date: fn(&str, u64, Option<u64>) -> String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum ItnLanguage {
+    #[default]
+    English,
+    French,
+}
+
+impl ItnLanguage {
+    pub fn from_code(code: &str) -> Option<Self> {
+        match code {
+            "en" => Some(Self::English),
+            "fr" => Some(Self::French),
+            _ => None,
+        }
+    }
+
+    fn grammar(&self) -> &'static Grammar {
+        match self {
+            Self::English => &en::GRAMMAR,
+            Self::French => &fr::GRAMMAR,
+        }
+    }
+}
+
+/// Apply ITN to a transcription if enabled in settings, in `language` when given.
+/// Text in a language without a grammar is left as is.
+pub fn normalize_with_settings(
+    settings: &AppSettings,
+    text: String,
+    language: Option<&str>,
+) -> String {
+    if !settings.itn_enabled {
+        return text;
+    }
+    let language = match language {
+        Some(code) => match ItnLanguage::from_code(code) {
+            Some(language) => language,
+            None => return text,
+        },
+        None => ItnLanguage::from_code(&settings.itn_language).unwrap_or_default(),
+    };
+    normalize(&text, language)
+}
+
+pub fn normalize(text: &str, language: ItnLanguage) -> String {
+    let grammar = language.grammar();
+    let tokens: Vec<Token> = text
+        .split_whitespace()
+        .map(|raw| Token::new(raw, grammar))
+        .collect();
+
+    let mut out = Vec::with_capacity(tokens.len());
+    let mut t = 0;
+    while t < tokens.len() {
+        match convert_at(&tokens[t..], grammar) {
+            Some((replacement, consumed)) => {
+                out.push(format!(
+                    "{}{}{}",
+                    tokens[t].lead,
+                    replacement,
+                    tokens[t + consumed - 1].trail
+                ));
+                t += consumed;
+            }
+            None => {
+                out.push(tokens[t].raw.clone());
+                t += 1;
+            }
+        }
+    }
+
+    out.join(" ")
+}
+
+/// A whitespace separated piece of the input, split into lowercase words.
+struct Token {
+    raw: String,
+    lead: String,
+    trail: String,
+    capitalized: bool,
+    words: Vec<String>,
+}
+
+impl Token {
+    fn new(raw: &str, grammar: &Grammar) -> Self {
+        let core_start = raw
+            .char_indices()
+            .find(|(_, c)| c.is_alphanumeric())
+            .map(|(i, _)| i)
+            .unwrap_or(raw.len());
+        let core_end = raw
+            .char_indices()
+            .rev()
+            .find(|(_, c)| c.is_alphanumeric())
+            .map(|(i, c)| i + c.len_utf8())
+            .unwrap_or(core_start);
+
+        let core = &raw[core_start..core_end];
+        let lower = core.to_lowercase();
+
+        // "vingt-trois" and "twenty-first" are matched word by word
+        let parts: Vec<&str> = lower.split('-').collect();
+        let words = if parts.len() > 1 && parts.iter().all(|p| is_known_word(p, grammar)) {
+            parts.iter().map(|p| p.to_string()).collect()
+        } else {
+            vec![lower.clone()]
+        };
+
+        Self {
+            raw: raw.to_string(),
+            lead: raw[..core_start].to_string(),
+            trail: raw[core_end..].to_string(),
+            capitalized: core.chars().next().is_some_and(|c| c.is_uppercase()),
+            words,
+        }
+    }
+}
+
+fn is_known_word(word: &str, grammar: &Grammar) -> bool {
+    grammar
+        .numbers
+        .iter()
+        .any(|(k, _)| k.split(' ').any(|p| p == word))
+        || grammar.ordinals.iter().any(|(k, _)| *k == word)
+}
+
+/// Try to convert a span starting at the first token.
+/// Returns the written form and the number of tokens it replaces.
+fn convert_at(tokens: &[Token], grammar: &Grammar) -> Option<(String, usize)> {
+    // A span never crosses punctuation: stop at the first token carrying some
+    let mut span = 0;
+    for (i, token) in tokens.iter().enumerate() {
+        if i > 0 && !token.lead.is_empty() {
+            break;
+        }
+        span = i + 1;
+        if !token.trail.is_empty() {
+            break;
+        }
+    }
+
+    let mut words: Vec<&str> = Vec::new();
+    let mut ends = Vec::with_capacity(span);
+    for token in &tokens[..span] {
+        words.extend(token.words.iter().map(|w| w.as_str()));
+        ends.push(words.len());
+    }
+    if words.iter().all(|w| w.is_empty()) {
+        return None;
+    }
+
+    let to_tokens = |consumed: usize| ends.iter().position(|&e| e == consumed).map(|i| i + 1);
+
+    if let Some((text, consumed)) = parse_date(&words, tokens[0].capitalized, grammar) {
+        if let Some(n) = to_tokens(consumed) {
+            return Some((text, n));
+        }
+    }
+
+    let number = parse_number(&words, grammar)?;
+    let rest = &words[number.consumed..];
+
+    let (text, consumed) = if let Some((symbol, n)) = lookup(grammar.units, rest) {
+        (format!("{}{}", number.text, symbol), number.consumed + n)
+    } else if let Some((symbol, n)) = lookup(grammar.currencies, rest) {
+        let text = if grammar.currency_prefix {
+            format!("{}{}", symbol, number.text)
+        } else {
+            format!("{} {}", number.text, symbol)
+        };
+        (text, number.consumed + n)
+    } else {
+        // Already written numbers and lone small numbers ("one of them") are left as is
+        if !number.spelled || (!number.decimal && number.consumed == 1 && number.value < 10) {
+            return None;
+        }
+        (number.text, number.consumed)
+    };
+
+    to_tokens(consumed).map(|n| (text, n))
+}
+
+struct Number {
+    text: String,
+    value: u64,
+    consumed: usize,
+    spelled: bool,
+    decimal: bool,
+}
+
+fn parse_number(words: &[&str], grammar: &Grammar) -> Option<Number> {
+    let (mut text, value, mut consumed, spelled) = match parse_digits(words.first()?) {
+        Some(digits) => {
+            let value = digits.parse().unwrap_or(u64::MAX);
+            (digits, value, 1, false)
+        }
+        None => {
+            let (value, consumed) = parse_cardinal(words, grammar)?;
+            (value.to_string(), value, consumed, true)
+        }
+    };
+
+    let mut decimal = false;
+    if let Some(n) = match_phrase(grammar.decimal_words, &words[consumed..]) {
+        if let Some((fraction, m)) = parse_fraction(&words[consumed + n..], grammar) {
+            text = format!("{}{}{}", text, grammar.decimal_separator, fraction);
+            consumed += n + m;
+            decimal = true;
+        }
+    }
+
+    Some(Number {
+        text,
+        value,
+        consumed,
+        spelled: spelled || decimal,
+        decimal,
+    })
+}
+
+/// Digits already written by the model ("23", "4.5", "4,5")
+fn parse_digits(word: &str) -> Option<String> {
+    let mut separators = 0;
+    let valid = word.chars().all(|c| {
+        if c == '.' || c == ',' {
+            separators += 1;
+            true
+        } else {
+            c.is_ascii_digit()
+        }
+    });
+    let first_and_last_digits = word.chars().next().is_some_and(|c| c.is_ascii_digit())
+        && word.chars().last().is_some_and(|c| c.is_ascii_digit());
+
+    if valid && separators <= 1 && first_and_last_digits {
+        Some(word.to_string())
+    } else {
+        None
+    }
+}
+
+/// Digits after the decimal word: "point two five" -> "25", "virgule vingt-cinq" -> "25"
+fn parse_fraction(words: &[&str], grammar: &Grammar) -> Option<(String, usize)> {
+    let mut digits = String::new();
+    let mut consumed = 0;
+    while let Some((NumberWord::Unit(v), 1)) = lookup(grammar.numbers, &words[consumed..]) {
+        digits.push_str(&v.to_string());
+        consumed += 1;
+    }
+    if consumed > 0 {
+        return Some((digits, consumed));
+    }
+
+    parse_cardinal(words, grammar).map(|(value, consumed)| (value.to_string(), consumed))
+}
+
+/// Parse the longest valid spoken cardinal at the start of `words`.
+fn parse_cardinal(words: &[&str], grammar: &Grammar) -> Option<(u64, usize)> {
+    let mut total: u64 = 0;
+    let mut current: u64 = 0;
+    let mut last: Option<NumberWord> = None;
+    let mut last_scale = u64::MAX;
+    let mut i = 0;
+
+    while i < words.len() {
+        let Some((word, len)) = lookup(grammar.numbers, &words[i..]) else {
+            break;
+        };
+        // Zero never combines with other number words
+        if last == Some(NumberWord::Unit(0)) {
+            break;
+        }
+
+        let accepted = match word {
+            NumberWord::Connector => {
+                let bridges = matches!(
+                    last,
+                    Some(NumberWord::Hundred | NumberWord::Scale(_) | NumberWord::Tens(_))
+                ) && match lookup(grammar.numbers, &words[i + len..]) {
+                    Some((NumberWord::Unit(v), _)) => v > 0,
+                    Some((NumberWord::Teen(_), _)) => {
+                        !matches!(last, Some(NumberWord::Tens(t)) if !grammar.tens_taking_teens.contains(&t))
+                    }
+                    _ => false,
+                };
+                if bridges {
+                    i += len;
+                    continue;
+                }
+                false
+            }
+            NumberWord::Unit(0) => last.is_none(),
+            NumberWord::Unit(v) => {
+                let ok = matches!(
+                    last,
+                    None | Some(NumberWord::Tens(_) | NumberWord::Hundred | NumberWord::Scale(_))
+                );
+                if ok {
+                    current += v;
+                }
+                ok
+            }
+            NumberWord::Teen(v) => {
+                let ok = match last {
+                    None | Some(NumberWord::Hundred | NumberWord::Scale(_)) => true,
+                    Some(NumberWord::Tens(t)) => grammar.tens_taking_teens.contains(&t),
+                    _ => false,
+                };
+                if ok {
+                    current += v;
+                }
+                ok
+            }
+            NumberWord::Tens(v) => {
+                let ok = matches!(
+                    last,
+                    None | Some(NumberWord::Hundred | NumberWord::Scale(_))
+                );
+                if ok {
+                    current += v;
+                }
+                ok
+            }
+            NumberWord::Hundred => {
+                let ok = current < 100
+                    && match last {
+                        Some(NumberWord::Unit(_) | NumberWord::Teen(_)) => true,
+                        Some(NumberWord::Scale(_)) => current == 0,
+                        None => grammar.bare_hundred,
+                        _ => false,
+                    };
+                if ok {
+                    current = current.max(1) * 100;
+                }
+                ok
+            }
+            NumberWord::Scale(scale) => {
+                let ok = scale < last_scale
+                    && match last {
+                        None => grammar.bare_scales.contains(&scale),
+                        Some(NumberWord::Scale(_)) => false,
+                        Some(_) => current > 0,
+                    };
+                if ok {
+                    total += current.max(1) * scale;
+                    current = 0;
+                    last_scale = scale;
+                }
+                ok
+            }
+        };
+
+        if !accepted {
+            break;
+        }
+        last = Some(word);
+        i += len;
+    }
+
+    if i == 0 {
+        None
+    } else {
+        Some((total + current, i))
+    }
+}
+
+fn parse_date(words: &[&str], capitalized: bool, grammar: &Grammar) -> Option<(String, usize)> {
+    // Day first: "the third of march", "trois mars"
+    let mut i = match_phrase(grammar.date_articles, words).unwrap_or(0);
+    if let Some((day, n)) = parse_day(&words[i..], grammar) {
+        i += n;
+        let linked = match match_phrase(grammar.date_links, &words[i..]) {
+            Some(n) => {
+                i += n;
+                true
+            }
+            None => grammar.date_links.is_empty(),
+        };
+        if linked {
+            if let Some((month, n)) = lookup(grammar.months, &words[i..]) {
+                i += n;
+                let (year, n) = parse_year(&words[i..], grammar).unwrap_or((None, 0));
+                return Some(((grammar.format_date)(month, day, year), i + n));
+            }
+        }
+    }
+
+    // Month first: "March third". Only trusted when the model capitalized the
+    // month, so that "you may first..." stays untouched.
+    if grammar.month_first_dates && capitalized {
+        let (month, mut i) = lookup(grammar.months, words)?;
+        i += match_phrase(grammar.date_articles, &words[i..]).unwrap_or(0);
+        let (day, n) = parse_day(&words[i..], grammar)?;
+        i += n;
+        let (year, n) = parse_year(&words[i..], grammar).unwrap_or((None, 0));
+        return Some(((grammar.format_date)(month, day, year), i + n));
+    }
+
+    None
+}
+
+fn parse_day(words: &[&str], grammar: &Grammar) -> Option<(u64, usize)> {
+    let first = words.first()?;
+
+    let (day, consumed) = if let Some((day, n)) = lookup(grammar.ordinals, words) {
+        (day, n)
+    } else if let Some(day) = grammar
+        .ordinal_suffixes
+        .iter()
+        .find_map(|s| first.strip_suffix(s))
+        .and_then(|d| d.parse::<u64>().ok())
+    {
+        (day, 1)
+    } else if let Some((NumberWord::Tens(tens), n)) = lookup(grammar.numbers, words) {
+        // "twenty first"
+        let (unit, m) = lookup(grammar.ordinals, &words[n..])?;
+        if unit >= 10 {
+            return None;
+        }
+        (tens + unit, n + m)
+    } else if grammar.cardinal_days {
+        match first.parse::<u64>() {
+            Ok(day) => (day, 1),
+            Err(_) => parse_cardinal(words, grammar)?,
+        }
+    } else {
+        return None;
+    };
+
+    if (1..=31).contains(&day) {
+        Some((day, consumed))
+    } else {
+        None
+    }
+}
+
+fn parse_year(words: &[&str], grammar: &Grammar) -> Option<(Option<u64>, usize)> {
+    let first = words.first()?;
+    if first.len() == 4 {
+        if let Ok(year) = first.parse::<u64>() {
+            return Some((Some(year), 1));
+        }
+    }
+
+    if let Some((year, n)) = parse_cardinal(words, grammar) {
+        if (1000..3000).contains(&year) {
+            return Some((Some(year), n));
+        }
+        // "twenty twenty five", "nineteen ninety"
+        if grammar.paired_years && (10..100).contains(&year) {
+            if let Some((low, m)) = parse_cardinal(&words[n..], grammar) {
+                if (10..100).contains(&low) {
+                    return Some((Some(year * 100 + low), n + m));
+                }
+            }
+        }
+    }
+
+    None
+}
+
+/// Longest entry of `table` matching the start of `words` (keys can hold several words).
+fn lookup<T: Copy>(table: &[(&str, T)], words: &[&str]) -> Option<(T, usize)> {
+    for len in (1..=words.len().min(3)).rev() {
+        let key = words[..len].join(" ");
+        if let Some((_, value)) = table.iter().find(|(k, _)| *k == key) {
+            return Some((*value, len));
+        }
+    }
+    None
+}
+
+fn match_phrase(phrases: &[&str], words: &[&str]) -> Option<usize> {
+    for len in (1..=words.len().min(3)).rev() {
+        let key = words[..len].join(" ");
+        if phrases.contains(&key.as_str()) {
+            return Some(len);
+        }
+    }
+    None
+}
@@ -0,0 +1,170 @@
+// Language identification of transcriptions
+//
+// The model is multilingual but does not say which language it heard, so the
+// language is guessed from the text: each language scores its most frequent
+// words and the letters only it uses. Dictation is short and full of function
+// words, which is enough to tell these languages apart.
+
+use crate::settings::AppSettings;
+
+struct Profile {
+    code: &'static str,
+    words: &'static [&'static str],
+    letters: &'static str,
+}
+
+const PROFILES: [Profile; 7] = [
+    Profile {
+        code: "en",
+        words: &[
+            "the", "and", "is", "are", "to", "of", "that", "it", "you", "i", "this", "with", "for",
+            "was", "have", "what", "not", "be", "on", "we", "they", "my", "can", "just", "so",
+            "do", "will", "would", "there",
+        ],
+        letters: "",
+    },
+    Profile {
+        code: "fr",
+        words: &[
+            "le", "la", "les", "et", "est", "un", "une", "des", "du", "de", "je", "tu", "il",
+            "nous", "vous", "que", "qui", "pas", "ce", "c", "j", "l", "d", "qu", "n", "pour",
+            "dans", "avec", "sur", "mais", "ça", "oui", "suis", "au",
+        ],
+        letters: "èëîïûœ",
+    },
+    Profile {
+        code: "de",
+        words: &[
+            "der", "die", "das", "und", "ist", "ein", "eine", "ich", "du", "nicht", "zu", "mit",
+            "den", "dem", "von", "auf", "es", "sie", "wir", "auch", "sich", "für", "aber", "wie",
+            "noch", "habe",
+        ],
+        letters: "äöüß",
+    },
+    Profile {
+        code: "es",
+        words: &[
+            "el", "la", "los", "las", "y", "es", "un", "una", "que", "de", "del", "en", "por",
+            "con", "para", "no", "se", "lo", "muy", "pero", "está", "como", "yo", "su", "al",
+            "hay", "también",
+        ],
+        letters: "ñ¿¡",
+    },
+    Profile {
+        code: "it",
+        words: &[
+            "il", "lo", "la", "gli", "le", "e", "è", "un", "una", "che", "di", "del", "della",
+            "in", "per", "con", "non", "si", "sono", "ma", "ho", "anche", "questo", "come", "mi",
+            "ci",
+        ],
+        letters: "",
+    },
+    Profile {
+        code: "pt",
+        words: &[
+            "o", "os", "a", "as", "e", "é", "um", "uma", "que", "de", "do", "da", "em", "no", "na",
+            "não", "para", "com", "por", "se", "eu", "você", "mais", "mas", "isso", "está",
+        ],
+        letters: "ãõ",
+    },
+    Profile {
+        code: "nl",
+        words: &[
+            "de", "het", "een", "en", "is", "van", "ik", "je", "niet", "dat", "die", "op", "te",
+            "met", "voor", "zijn", "maar", "ook", "wat", "er", "we", "hij", "naar", "nog",
+        ],
+        letters: "",
+    },
+];
+
+/// Fewest points the best language needs, so that "OK" or a lone name is not guessed.
+const MIN_SCORE: usize = 2;
+
+/// Guess the language of a text, as a code like "fr".
+/// None when the text is too short or too ambiguous to tell.
+pub fn detect(text: &str) -> Option<String> {
+    let lower = text.to_lowercase();
+    // "c'est" and "l’heure" count as "c" and "l"
+    let words: Vec<&str> = lower
+        .split(|c: char| c.is_whitespace() || c == '\'' || c == '’')
+        .map(|w| w.trim_matches(|c: char| !c.is_alphanumeric()))
+        .filter(|w| !w.is_empty())
+        .collect();
+
+    let mut scores: Vec<(usize, &str)> = PROFILES
+        .iter()
+        .map(|profile| {
+            let words = words.iter().filter(|w| profile.words.contains(w)).count();
+            let letters = lower
+                .chars()
+                .filter(|c| profile.letters.contains(*c))
+                .count();
+            (words + letters, profile.code)
+        })
+        .collect();
+    scores.sort_by_key(|(score, _)| std::cmp::Reverse(*score));
+
+    let (best, code) = scores[0];
+    let runner_up = scores[1].0;
+    if best < MIN_SCORE || best == runner_up {
+        return None;
+    }
+    Some(code.to_string())
+}
+
+/// Language the dictionary, ITN and filler removal should use: the detected one
+/// when detection is on. None leaves them on their configured languages.
+pub fn processing_language<'a>(
+    settings: &AppSettings,
+    detected: Option<&'a str>,
+) -> Option<&'a str> {
+    detected.filter(|_| settings.language_detection_enabled)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn check(cases: &[(&str, Option<&str>)]) {
+        for (text, expected) in cases {
+            assert_eq!(detect(text).as_deref(), *expected, "{text}");
+        }
+    }
+
+    #[test]
+    fn short_sentences() {
+        check(&[
+            ("I think that we should go", Some("en")),
+            ("Can you send it to me?", Some("en")),
+            ("Je pense que c'est bien", Some("fr")),
+            ("Il n'est pas là", Some("fr")),
+            ("Ich habe das nicht gesehen", Some("de")),
+            ("Wir sind auch da", Some("de")),
+            ("No sé si está en casa", Some("es")),
+            ("Hay que ir con ellos", Some("es")),
+        ]);
+    }
+
+    #[test]
+    fn letters_only_one_language_uses() {
+        check(&[
+            ("Grüße", Some("de")),
+            ("¿Mañana?", Some("es")),
+            ("Sœur aînée", Some("fr")),
+        ]);
+    }
+
+    #[test]
+    fn too_short_or_ambiguous() {
+        check(&[
+            ("", None),
+            ("OK", None),
+            ("Merci", None),
+            ("Call Ben", None),
+            ("la", None),
+            // Function words several languages share
+            ("de la", None),
+            ("que de", None),
+        ]);
+    }
+}
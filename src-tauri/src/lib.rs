@@ -2,13 +2,23 @@ mod audio;
 mod clipboard;
 mod commands;
 mod dictionary;
+mod disfluency;
 mod engine;
+mod error;
+mod focus;
+mod folder_import;
 mod history;
 mod http_api;
+mod itn;
+mod language;
+mod live_preview;
+mod memory;
 mod model;
 mod overlay;
+mod profiles;
 mod settings;
 mod shortcuts;
+mod transcription;
 mod tray_icon;
 
 use audio::preload_engine;
@@ -21,21 +31,44 @@ use std::sync::Arc;
 use tauri::{DeviceEventFilter, Manager};
 use tray_icon::setup_tray;
 
-use crate::shortcuts::{LastTranscriptShortcutKeys, RecordShortcutKeys, TranscriptionSuspended};
+use crate::shortcuts::{
+    CustomShortcuts, LastTranscriptShortcutKeys, MatchMode, MaxRecordingDuration, RecordMode,
+    RecordShortcutKeys, RecordShortcutMode, ShortcutMatchMode, TranscriptionSuspended,
+};
 
 fn show_main_window(app: &tauri::AppHandle) {
     if let Some(main_window) = app.get_webview_window("main") {
         if let Err(e) = main_window.show() {
-            eprintln!("Failed to show window: {}", e);
+            log::error!("Failed to show window: {}", e);
         }
         if let Err(e) = main_window.set_focus() {
-            eprintln!("Failed to focus window: {}", e);
+            log::error!("Failed to focus window: {}", e);
         }
     } else {
-        eprintln!("Main window not found");
+        log::warn!("Main window not found");
     }
 }
 
+/// Log to stdout and to rotating files under the app data dir.
+fn setup_logging(app: &tauri::AppHandle) -> tauri::Result<()> {
+    use tauri_plugin_log::{RotationStrategy, Target, TargetKind};
+
+    let logs_dir = app.path().app_data_dir()?.join("logs");
+    app.plugin(
+        tauri_plugin_log::Builder::new()
+            .clear_targets()
+            .target(Target::new(TargetKind::Stdout))
+            .target(Target::new(TargetKind::Folder {
+                path: logs_dir,
+                file_name: Some("murmure".to_string()),
+            }))
+            .max_file_size(2_000_000)
+            .rotation_strategy(RotationStrategy::KeepSome(5))
+            .level(log::LevelFilter::Info)
+            .build(),
+    )
+}
+
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
     tauri::Builder::default()
@@ -49,6 +82,8 @@ pub fn run() {
         .plugin(tauri_plugin_clipboard_manager::init())
         .device_event_filter(DeviceEventFilter::Never)
         .setup(|app| {
+            setup_logging(&app.handle())?;
+
             let model =
                 Arc::new(Model::new(app.handle().clone()).expect("Failed to initialize model"));
             app.manage(model);
@@ -58,13 +93,13 @@ pub fn run() {
             app.manage(HttpApiState::new());
 
             match preload_engine(&app.handle()) {
-                Ok(_) => println!("Transcription engine ready"),
-                Err(e) => println!("Transcription engine will be loaded on first use: {}", e),
+                Ok(_) => log::info!("Transcription engine ready"),
+                Err(e) => log::warn!("Transcription engine will be loaded on first use: {}", e),
             }
 
-            setup_tray(&app.handle())?;
-
             overlay::create_recording_overlay(&app.handle());
+            overlay::watch_monitors(&app.handle());
+            memory::watch_idle_engine(&app.handle());
             if s.overlay_mode.as_str() == "always" {
                 if let Some(overlay_window) = app.get_webview_window("recording_overlay") {
                     let _ = overlay_window.show();
@@ -77,10 +112,22 @@ pub fn run() {
             let last_transcript_keys = shortcuts::parse_binding_keys(&s.last_transcript_shortcut);
             app.manage(LastTranscriptShortcutKeys::new(last_transcript_keys));
 
+            app.manage(CustomShortcuts::new(&s.custom_shortcuts));
+
+            let record_mode = RecordMode::parse(&s.record_mode).unwrap_or(RecordMode::PushToTalk);
+            app.manage(RecordShortcutMode::new(record_mode));
+            app.manage(MaxRecordingDuration::new(s.max_recording_seconds));
+
+            let match_mode = MatchMode::parse(&s.shortcut_match_mode).unwrap_or(MatchMode::Exact);
+            app.manage(ShortcutMatchMode::new(match_mode));
+
             app.manage(TranscriptionSuspended::new(false));
 
+            transcription::init_transcription_worker(&app.handle());
             init_shortcuts(app.handle().clone());
 
+            setup_tray(&app.handle())?;
+
             if s.api_enabled {
                 let app_handle = app.handle().clone();
                 let state = app_handle.state::<HttpApiState>().inner().clone();
@@ -98,17 +145,47 @@ pub fn run() {
         .invoke_handler(tauri::generate_handler![
             is_model_available,
             get_model_path,
+            get_installed_models,
+            get_available_models,
+            download_model,
+            delete_model,
+            get_active_model,
+            set_active_model,
+            get_model_source,
+            set_model_source,
+            get_engines,
+            get_engine,
+            set_engine,
+            get_model_precision,
+            set_model_precision,
+            get_loaded_model,
+            get_onnx_runtime_settings,
+            set_onnx_runtime_settings,
+            get_model_idle_unload_minutes,
+            set_model_idle_unload_minutes,
+            get_prewarm_model_on_record,
+            set_prewarm_model_on_record,
+            get_memory_usage,
+            transcribe_folder,
             get_recent_transcriptions,
             get_record_shortcut,
             set_record_shortcut,
             set_dictionary,
             get_dictionary,
+            get_dictionary_skip_confidence,
+            set_dictionary_skip_confidence,
             get_last_transcript_shortcut,
             set_last_transcript_shortcut,
             get_overlay_mode,
             set_overlay_mode,
             get_overlay_position,
             set_overlay_position,
+            get_overlay_monitor,
+            set_overlay_monitor,
+            get_monitors,
+            save_overlay_custom_position,
+            get_overlay_style,
+            set_overlay_style,
             suspend_transcription,
             resume_transcription,
             get_api_enabled,
@@ -117,6 +194,37 @@ pub fn run() {
             set_api_port,
             start_http_api_server,
             stop_http_api_server,
+            get_itn_enabled,
+            set_itn_enabled,
+            get_itn_language,
+            set_itn_language,
+            get_language_detection_enabled,
+            set_language_detection_enabled,
+            get_filler_removal_enabled,
+            set_filler_removal_enabled,
+            get_filler_words,
+            set_filler_words,
+            get_output_strategy,
+            set_output_strategy,
+            get_paste_shortcut,
+            set_paste_shortcut,
+            get_output_profiles,
+            set_output_profiles,
+            get_record_mode,
+            set_record_mode,
+            get_max_recording_seconds,
+            set_max_recording_seconds,
+            get_shortcut_match_mode,
+            set_shortcut_match_mode,
+            get_shortcut_actions,
+            get_custom_shortcuts,
+            set_custom_shortcuts,
+            get_linux_input_backend,
+            set_linux_input_backend,
+            get_active_input_backend,
+            get_microphones,
+            get_microphone,
+            set_microphone,
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
@@ -0,0 +1,48 @@
+use crate::audio;
+use crate::overlay::{self, OverlayUpdate};
+use std::time::{Duration, Instant};
+use tauri::AppHandle;
+
+const TICK: Duration = Duration::from_millis(250);
+const PARTIAL_INTERVAL: Duration = Duration::from_millis(1500);
+/// Only the end of the recording is transcribed, so each pass stays quick
+pub const PARTIAL_WINDOW_SECONDS: usize = 15;
+
+/// Feed the expanded overlay with the elapsed time and a rolling transcript
+/// until the current recording stops.
+pub fn start(app: &AppHandle) {
+    let Some(recording) = audio::current_recording() else {
+        return;
+    };
+    overlay::reset_overlay_size(app);
+    overlay::emit_overlay_update(app, OverlayUpdate::Elapsed { seconds: 0 });
+
+    let app = app.clone();
+    let is_current = move || audio::current_recording().as_ref() == Some(&recording);
+    std::thread::spawn(move || {
+        let started = Instant::now();
+        let mut last_elapsed = 0;
+        let mut last_partial = Instant::now();
+        loop {
+            std::thread::sleep(TICK);
+            if !is_current() {
+                break;
+            }
+
+            let elapsed = started.elapsed().as_secs();
+            if elapsed != last_elapsed {
+                last_elapsed = elapsed;
+                overlay::emit_overlay_update(&app, OverlayUpdate::Elapsed { seconds: elapsed });
+            }
+
+            if last_partial.elapsed() >= PARTIAL_INTERVAL {
+                last_partial = Instant::now();
+                let text = audio::transcribe_live_tail(PARTIAL_WINDOW_SECONDS);
+                // The recording may have stopped meanwhile, and the final text must win
+                if let Some(text) = text.filter(|text| !text.is_empty() && is_current()) {
+                    overlay::emit_overlay_update(&app, OverlayUpdate::Partial { text });
+                }
+            }
+        }
+    });
+}
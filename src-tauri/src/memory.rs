@@ -0,0 +1,85 @@
+use crate::audio;
+use crate::settings;
+use crate::transcription::TranscriptionQueue;
+use serde::Serialize;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager};
+
+const IDLE_CHECK_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Payload of the `get_memory_usage` command.
+#[derive(Serialize, Clone, Debug)]
+pub struct MemoryUsage {
+    /// Resident memory of the whole process, when the platform reports it
+    pub resident_bytes: Option<u64>,
+    pub model_loaded: bool,
+    /// Size of the loaded ONNX files, roughly what the model takes in memory
+    pub model_bytes: u64,
+    /// Time since the model was last used, when loaded
+    pub idle_seconds: Option<u64>,
+}
+
+pub fn memory_usage() -> MemoryUsage {
+    let loaded = audio::loaded_model();
+    let model_bytes = loaded.as_ref().map_or(0, |info| {
+        info.onnx_files
+            .iter()
+            .filter_map(|file| std::fs::metadata(std::path::Path::new(&info.path).join(file)).ok())
+            .map(|metadata| metadata.len())
+            .sum()
+    });
+    MemoryUsage {
+        resident_bytes: resident_bytes(),
+        model_loaded: loaded.is_some(),
+        model_bytes,
+        idle_seconds: audio::engine_idle_time().map(|idle| idle.as_secs()),
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn resident_bytes() -> Option<u64> {
+    let status = std::fs::read_to_string("/proc/self/status").ok()?;
+    let line = status.lines().find(|line| line.starts_with("VmRSS:"))?;
+    let kb: u64 = line.split_whitespace().nth(1)?.parse().ok()?;
+    Some(kb * 1024)
+}
+
+#[cfg(target_os = "windows")]
+fn resident_bytes() -> Option<u64> {
+    use windows_sys::Win32::System::ProcessStatus::{
+        GetProcessMemoryInfo, PROCESS_MEMORY_COUNTERS,
+    };
+    use windows_sys::Win32::System::Threading::GetCurrentProcess;
+
+    let mut counters: PROCESS_MEMORY_COUNTERS = unsafe { std::mem::zeroed() };
+    let size = std::mem::size_of::<PROCESS_MEMORY_COUNTERS>() as u32;
+    if unsafe { GetProcessMemoryInfo(GetCurrentProcess(), &mut counters, size) } == 0 {
+        return None;
+    }
+    Some(counters.WorkingSetSize as u64)
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "windows")))]
+fn resident_bytes() -> Option<u64> {
+    None
+}
+
+/// Unload the model once it has not been used for `model_idle_unload_minutes`.
+/// It is loaded again on the next recording, see `audio::prewarm_engine`.
+pub fn watch_idle_engine(app: &AppHandle) {
+    let app = app.clone();
+    std::thread::spawn(move || loop {
+        std::thread::sleep(IDLE_CHECK_INTERVAL);
+        let minutes = settings::load_settings(&app).model_idle_unload_minutes;
+        if minutes == 0 {
+            continue;
+        }
+        let busy = audio::is_recording()
+            || app
+                .try_state::<TranscriptionQueue>()
+                .is_some_and(|queue| queue.pending() > 0);
+        if !busy && audio::unload_engine_if_idle(Duration::from_secs(minutes * 60)) {
+            let _ = app.emit("model-unloaded", ());
+        }
+    });
+}
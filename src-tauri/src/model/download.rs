@@ -0,0 +1,389 @@
+use super::{is_valid_model_name, Model, ModelInfo, MODEL_INFO_FILE};
+use anyhow::{Context, Result};
+use flate2::read::GzDecoder;
+use futures_util::StreamExt;
+use once_cell::sync::Lazy;
+use parking_lot::Mutex;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::collections::HashSet;
+use std::fs::{self, File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter};
+
+const MANIFEST_FILE: &str = "manifest.json";
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Models being downloaded, so the same one is not fetched twice at once.
+static DOWNLOADS: Lazy<Mutex<HashSet<String>>> = Lazy::new(|| Mutex::new(HashSet::new()));
+
+/// Payload of the "model-download-progress" event.
+#[derive(Serialize, Clone, Debug)]
+pub struct DownloadProgress {
+    pub name: String,
+    pub downloaded: u64,
+    pub total: u64,
+}
+
+struct DownloadGuard(String);
+
+impl DownloadGuard {
+    fn acquire(name: &str) -> Result<Self> {
+        if !DOWNLOADS.lock().insert(name.to_string()) {
+            anyhow::bail!("Model '{}' is already being downloaded", name);
+        }
+        Ok(Self(name.to_string()))
+    }
+}
+
+impl Drop for DownloadGuard {
+    fn drop(&mut self) {
+        DOWNLOADS.lock().remove(&self.0);
+    }
+}
+
+fn resolve_url(source: &str, url: &str) -> String {
+    if url.starts_with("http://") || url.starts_with("https://") {
+        return url.to_string();
+    }
+    format!(
+        "{}/{}",
+        source.trim_end_matches('/'),
+        url.trim_start_matches('/')
+    )
+}
+
+/// Models offered by the download source, from its manifest.json.
+pub async fn fetch_manifest(source: &str) -> Result<Vec<ModelInfo>> {
+    if source.is_empty() {
+        anyhow::bail!("No model download source is configured");
+    }
+    let url = resolve_url(source, MANIFEST_FILE);
+    let models = reqwest::get(&url)
+        .await
+        .and_then(|response| response.error_for_status())
+        .with_context(|| format!("Failed to fetch {}", url))?
+        .json::<Vec<ModelInfo>>()
+        .await
+        .with_context(|| format!("Invalid model manifest at {}", url))?;
+    Ok(models
+        .into_iter()
+        .filter(|model| is_valid_model_name(&model.name))
+        .collect())
+}
+
+/// Download, verify and extract a model into the models dir, resuming an
+/// interrupted download of the same archive.
+pub async fn download_model(
+    app: &AppHandle,
+    model: &Model,
+    info: &ModelInfo,
+    source: &str,
+) -> Result<PathBuf> {
+    if !is_valid_model_name(&info.name) {
+        anyhow::bail!("Invalid model name '{}'", info.name);
+    }
+    let _guard = DownloadGuard::acquire(&info.name)?;
+
+    let models_dir = model.models_dir()?;
+    ensure_not_loaded(&models_dir.join(&info.name))?;
+    let downloads_dir = models_dir.join(".downloads");
+    fs::create_dir_all(&downloads_dir)?;
+    let partial = downloads_dir.join(format!("{}.tar.gz.part", info.name));
+
+    let mut downloaded = fs::metadata(&partial).map(|m| m.len()).unwrap_or(0);
+    if info.size == 0 || downloaded < info.size {
+        let emit_progress = |downloaded: u64, total: u64| {
+            let _ = app.emit(
+                "model-download-progress",
+                DownloadProgress {
+                    name: info.name.clone(),
+                    downloaded,
+                    total,
+                },
+            );
+        };
+        downloaded = fetch_archive(
+            info,
+            &resolve_url(source, &info.url),
+            &partial,
+            downloaded,
+            emit_progress,
+        )
+        .await?;
+    }
+    log::info!("Downloaded {} bytes of model '{}'", downloaded, info.name);
+
+    let info = info.clone();
+    tauri::async_runtime::spawn_blocking(move || -> Result<PathBuf> {
+        verify_checksum(&partial, &info.sha256).inspect_err(|_| {
+            // Start over next time rather than resume a corrupt file
+            let _ = fs::remove_file(&partial);
+        })?;
+        // It may have been loaded during the download
+        ensure_not_loaded(&models_dir.join(&info.name))?;
+        let path = extract(&partial, &models_dir, &info.name)?;
+        fs::remove_file(&partial)?;
+        fs::write(
+            path.join(MODEL_INFO_FILE),
+            serde_json::to_string_pretty(&info)?,
+        )?;
+        log::info!("Model '{}' installed at {}", info.name, path.display());
+        Ok(path)
+    })
+    .await?
+}
+
+/// Refuse to replace the model the engine runs on, its files are in use.
+fn ensure_not_loaded(target: &Path) -> Result<()> {
+    let loaded = crate::audio::loaded_model();
+    if loaded.is_some_and(|loaded| Path::new(&loaded.path).starts_with(target)) {
+        anyhow::bail!(
+            "Model '{}' is in use, switch to another model before reinstalling it",
+            target.file_name().unwrap_or_default().to_string_lossy()
+        );
+    }
+    Ok(())
+}
+
+/// Append the rest of the archive to `partial`, returning its final size.
+/// `on_progress` gets the downloaded and total sizes.
+async fn fetch_archive(
+    info: &ModelInfo,
+    url: &str,
+    partial: &Path,
+    mut downloaded: u64,
+    on_progress: impl Fn(u64, u64),
+) -> Result<u64> {
+    let mut request = reqwest::Client::new().get(url);
+    if downloaded > 0 {
+        request = request.header(reqwest::header::RANGE, format!("bytes={}-", downloaded));
+    }
+    let response = request
+        .send()
+        .await
+        .and_then(|response| response.error_for_status())
+        .with_context(|| format!("Failed to download {}", url))?;
+
+    let resumed = response.status() == reqwest::StatusCode::PARTIAL_CONTENT;
+    let mut file = if resumed {
+        log::info!(
+            "Resuming download of '{}' at {} bytes",
+            info.name,
+            downloaded
+        );
+        OpenOptions::new().append(true).open(partial)?
+    } else {
+        if downloaded > 0 {
+            log::info!("Server cannot resume, downloading '{}' again", info.name);
+        }
+        downloaded = 0;
+        File::create(partial)?
+    };
+    let total = response
+        .content_length()
+        .map_or(info.size, |len| len + downloaded);
+
+    on_progress(downloaded, total);
+
+    let mut stream = response.bytes_stream();
+    let mut last_emit = Instant::now();
+    while let Some(chunk) = stream.next().await {
+        let chunk = chunk.with_context(|| format!("Download of '{}' interrupted", info.name))?;
+        file.write_all(&chunk)?;
+        downloaded += chunk.len() as u64;
+        if last_emit.elapsed() >= PROGRESS_INTERVAL {
+            on_progress(downloaded, total);
+            last_emit = Instant::now();
+        }
+    }
+    file.flush()?;
+    on_progress(downloaded, total);
+    Ok(downloaded)
+}
+
+fn verify_checksum(path: &Path, expected: &str) -> Result<()> {
+    let mut file = File::open(path)?;
+    let mut hasher = Sha256::new();
+    std::io::copy(&mut file, &mut hasher)?;
+    let actual = format!("{:x}", hasher.finalize());
+    if !actual.eq_ignore_ascii_case(expected) {
+        anyhow::bail!(
+            "Checksum mismatch for {}: expected {}, got {}",
+            path.display(),
+            expected,
+            actual
+        );
+    }
+    Ok(())
+}
+
+/// Unpack the archive into `models_dir/name`, replacing any previous copy.
+fn extract(archive: &Path, models_dir: &Path, name: &str) -> Result<PathBuf> {
+    let staging = models_dir.join(".downloads").join(name);
+    if staging.exists() {
+        fs::remove_dir_all(&staging)?;
+    }
+    tar::Archive::new(GzDecoder::new(File::open(archive)?))
+        .unpack(&staging)
+        .with_context(|| format!("Failed to extract {}", archive.display()))?;
+
+    // Archives usually hold a single top-level folder
+    let mut entries = fs::read_dir(&staging)?.collect::<Result<Vec<_>, _>>()?;
+    let root = if entries.len() == 1 && entries[0].path().is_dir() {
+        entries.remove(0).path()
+    } else {
+        staging.clone()
+    };
+
+    let target = models_dir.join(name);
+    if target.exists() {
+        fs::remove_dir_all(&target)?;
+    }
+    fs::rename(&root, &target)?;
+    if staging.exists() {
+        fs::remove_dir_all(&staging)?;
+    }
+    Ok(target)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flate2::{write::GzEncoder, Compression};
+    use std::io::{BufRead, BufReader};
+    use std::net::TcpListener;
+
+    fn temp_dir() -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("murmure-test-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn fixture() -> Vec<u8> {
+        (0..10_000u32).map(|i| (i % 251) as u8).collect()
+    }
+
+    fn info(data: &[u8]) -> ModelInfo {
+        ModelInfo {
+            name: "test-model".to_string(),
+            url: "model.tar.gz".to_string(),
+            sha256: format!("{:x}", Sha256::digest(data)),
+            size: data.len() as u64,
+            languages: Vec::new(),
+        }
+    }
+
+    /// Serve `data` to one request, honouring "Range: bytes=N-" when `ranges` is set.
+    fn serve(data: Vec<u8>, ranges: bool) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/model.tar.gz", listener.local_addr().unwrap());
+        std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+            let mut start = 0;
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                if line.trim().is_empty() {
+                    break;
+                }
+                if let Some(range) = line.to_ascii_lowercase().strip_prefix("range: bytes=") {
+                    start = range.trim().trim_end_matches('-').parse().unwrap();
+                }
+            }
+            let (status, body) = if ranges && start > 0 {
+                ("206 Partial Content", &data[start..])
+            } else {
+                ("200 OK", &data[..])
+            };
+            write!(
+                stream,
+                "HTTP/1.1 {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                status,
+                body.len()
+            )
+            .unwrap();
+            stream.write_all(body).unwrap();
+        });
+        url
+    }
+
+    #[tokio::test]
+    async fn resumes_a_partial_download() {
+        let data = fixture();
+        let partial = temp_dir().join("test-model.tar.gz.part");
+        fs::write(&partial, &data[..4000]).unwrap();
+
+        let url = serve(data.clone(), true);
+        let total = std::sync::Mutex::new(0);
+        let downloaded = fetch_archive(&info(&data), &url, &partial, 4000, |_, t| {
+            *total.lock().unwrap() = t
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(downloaded, data.len() as u64);
+        assert_eq!(*total.lock().unwrap(), data.len() as u64);
+        assert_eq!(fs::read(&partial).unwrap(), data);
+        verify_checksum(&partial, &info(&data).sha256).unwrap();
+    }
+
+    #[tokio::test]
+    async fn starts_over_when_the_server_cannot_resume() {
+        let data = fixture();
+        let partial = temp_dir().join("test-model.tar.gz.part");
+        fs::write(&partial, [0xFF; 4000]).unwrap();
+
+        let url = serve(data.clone(), false);
+        let downloaded = fetch_archive(&info(&data), &url, &partial, 4000, |_, _| {})
+            .await
+            .unwrap();
+
+        assert_eq!(downloaded, data.len() as u64);
+        assert_eq!(fs::read(&partial).unwrap(), data);
+    }
+
+    #[test]
+    fn checksum_rejects_a_truncated_file() {
+        let data = fixture();
+        let partial = temp_dir().join("test-model.tar.gz.part");
+        fs::write(&partial, &data[..4000]).unwrap();
+
+        let error = verify_checksum(&partial, &info(&data).sha256).unwrap_err();
+        assert!(error.to_string().contains("Checksum mismatch"), "{error}");
+        // Case of the expected digest does not matter
+        verify_checksum(&partial, &info(&data[..4000]).sha256.to_uppercase()).unwrap();
+    }
+
+    #[test]
+    fn extract_replaces_the_previous_copy() {
+        let models_dir = temp_dir();
+        let archive = models_dir.join("model.tar.gz");
+        let mut builder = tar::Builder::new(GzEncoder::new(
+            File::create(&archive).unwrap(),
+            Compression::default(),
+        ));
+        let mut header = tar::Header::new_gnu();
+        header.set_size(5);
+        header.set_mode(0o644);
+        header.set_cksum();
+        builder
+            .append_data(&mut header, "model-v2/encoder.onnx", &b"onnx!"[..])
+            .unwrap();
+        builder.into_inner().unwrap().finish().unwrap();
+
+        let previous = models_dir.join("test-model");
+        fs::create_dir_all(&previous).unwrap();
+        fs::write(previous.join("stale.onnx"), "old").unwrap();
+
+        let target = extract(&archive, &models_dir, "test-model").unwrap();
+        assert_eq!(target, previous);
+        // The single top-level folder is unwrapped
+        assert_eq!(fs::read(target.join("encoder.onnx")).unwrap(), b"onnx!");
+        assert!(!target.join("stale.onnx").exists());
+        assert!(!models_dir.join(".downloads").join("test-model").exists());
+    }
+}
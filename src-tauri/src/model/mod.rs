@@ -0,0 +1,210 @@
+mod download;
+
+use crate::settings;
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Manager};
+
+pub use download::{download_model, fetch_manifest, DownloadProgress};
+
+/// Shipped with the app, the default active model.
+pub const MODEL_FILENAME: &str = "parakeet-tdt-0.6b-v3-int8";
+/// Written next to a downloaded model, so it can be listed with its manifest entry.
+const MODEL_INFO_FILE: &str = "murmure-model.json";
+
+/// A downloadable model, as listed in the manifest of the download source.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ModelInfo {
+    /// Also the folder name once extracted
+    pub name: String,
+    /// Of the .tar.gz archive, relative to the download source unless absolute
+    pub url: String,
+    pub sha256: String,
+    /// Archive size in bytes
+    pub size: u64,
+    #[serde(default)]
+    pub languages: Vec<String>,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct InstalledModel {
+    pub name: String,
+    pub path: String,
+    pub bundled: bool,
+    pub active: bool,
+    pub languages: Vec<String>,
+}
+
+/// Model names end up as folder names, keep them to a single path component.
+pub fn is_valid_model_name(name: &str) -> bool {
+    !name.is_empty()
+        && !name.starts_with('.')
+        && !name.contains(['/', '\\'])
+        && Path::new(name).file_name().is_some_and(|n| n == name)
+}
+
+pub struct Model {
+    app_handle: AppHandle,
+}
+
+impl Model {
+    pub fn new(app_handle: AppHandle) -> Result<Self> {
+        Ok(Self { app_handle })
+    }
+
+    /// Where downloaded models are extracted.
+    pub fn models_dir(&self) -> Result<PathBuf> {
+        let dir = self.app_handle.path().app_data_dir()?.join("models");
+        fs::create_dir_all(&dir)?;
+        Ok(dir)
+    }
+
+    /// Path of the active model.
+    pub fn get_model_path(&self) -> Result<PathBuf> {
+        let s = settings::load_settings(&self.app_handle);
+        self.model_path(&s.active_model)
+    }
+
+    pub fn model_path(&self, name: &str) -> Result<PathBuf> {
+        if is_valid_model_name(name) {
+            let downloaded = self.models_dir()?.join(name);
+            if downloaded.is_dir() {
+                return Ok(downloaded);
+            }
+        }
+        if name == MODEL_FILENAME {
+            return self.bundled_model_path();
+        }
+        anyhow::bail!("Model '{}' is not installed", name)
+    }
+
+    fn bundled_model_path(&self) -> Result<PathBuf> {
+        // Essayer plusieurs emplacements possibles pour le modèle
+        let possible_paths = vec![
+            // 1. Chemin pour la production (bundle)
+            self.app_handle.path().resolve(
+                format!("resources/{}", MODEL_FILENAME),
+                tauri::path::BaseDirectory::Resource,
+            ),
+            // 2. Chemin relatif depuis Resource (Windows prod)
+            self.app_handle.path().resolve(
+                format!("../resources/{}", MODEL_FILENAME),
+                tauri::path::BaseDirectory::Resource,
+            ),
+            // 3. Chemin pour le développement
+            self.app_handle.path().resolve(
+                format!("_up_/resources/{}", MODEL_FILENAME),
+                tauri::path::BaseDirectory::Resource,
+            ),
+        ];
+
+        // Essayer chaque chemin
+        for path_result in possible_paths {
+            if let Ok(model_path) = path_result {
+                if model_path.exists() {
+                    log::info!("Model found at: {}", model_path.display());
+                    return Ok(model_path);
+                } else {
+                    log::warn!("Model not found at: {}", model_path.display());
+                }
+            }
+        }
+
+        // Si aucun chemin ne fonctionne, essayer le chemin absolu depuis AppData/Exe
+        let exe_dir = self.app_handle.path().app_data_dir()?;
+        let fallback_path = exe_dir.join("resources").join(MODEL_FILENAME);
+
+        if fallback_path.exists() {
+            log::info!(
+                "Model found at fallback location: {}",
+                fallback_path.display()
+            );
+            return Ok(fallback_path);
+        }
+
+        // Dernier recours : chemin relatif depuis le binaire
+        if let Ok(exe_path) = std::env::current_exe() {
+            if let Some(exe_dir) = exe_path.parent() {
+                let dev_path = exe_dir.join("_up_").join("resources").join(MODEL_FILENAME);
+                if dev_path.exists() {
+                    log::info!("Model found at dev location: {}", dev_path.display());
+                    return Ok(dev_path);
+                }
+            }
+        }
+
+        anyhow::bail!(
+            "Model '{}' not found in any expected location. \
+            Please ensure the model is in the resources folder.",
+            MODEL_FILENAME
+        )
+    }
+
+    pub fn is_available(&self) -> bool {
+        self.get_model_path().is_ok()
+    }
+
+    pub fn installed_models(&self) -> Result<Vec<InstalledModel>> {
+        let active = settings::load_settings(&self.app_handle).active_model;
+        let mut models = Vec::new();
+
+        let models_dir = self.models_dir()?;
+        for entry in fs::read_dir(&models_dir)? {
+            let path = entry?.path();
+            let Some(name) = path.file_name().and_then(|n| n.to_str()) else {
+                continue;
+            };
+            if !path.is_dir() || !is_valid_model_name(name) {
+                continue;
+            }
+            let languages = fs::read_to_string(path.join(MODEL_INFO_FILE))
+                .ok()
+                .and_then(|content| serde_json::from_str::<ModelInfo>(&content).ok())
+                .map(|info| info.languages)
+                .unwrap_or_default();
+            models.push(InstalledModel {
+                name: name.to_string(),
+                path: path.to_string_lossy().to_string(),
+                bundled: false,
+                active: name == active,
+                languages,
+            });
+        }
+
+        // A downloaded copy takes precedence, see `model_path`
+        if !models.iter().any(|m| m.name == MODEL_FILENAME) {
+            if let Ok(path) = self.bundled_model_path() {
+                models.insert(
+                    0,
+                    InstalledModel {
+                        name: MODEL_FILENAME.to_string(),
+                        path: path.to_string_lossy().to_string(),
+                        bundled: true,
+                        active: active == MODEL_FILENAME,
+                        languages: Vec::new(),
+                    },
+                );
+            }
+        }
+        Ok(models)
+    }
+
+    /// Delete a downloaded model. The bundled and the active ones are kept.
+    pub fn delete_model(&self, name: &str) -> Result<()> {
+        if !is_valid_model_name(name) {
+            anyhow::bail!("Invalid model name '{}'", name);
+        }
+        if settings::load_settings(&self.app_handle).active_model == name {
+            anyhow::bail!("Switch to another model before deleting '{}'", name);
+        }
+        let path = self.models_dir()?.join(name);
+        if !path.is_dir() {
+            anyhow::bail!("Model '{}' was not downloaded", name);
+        }
+        fs::remove_dir_all(&path)?;
+        log::info!("Model '{}' deleted", name);
+        Ok(())
+    }
+}
@@ -1,46 +1,146 @@
-use crate::settings;
-use tauri::{AppHandle, Emitter, Manager, WebviewWindowBuilder};
+use crate::focus;
+use crate::settings::{self, AppSettings};
+use once_cell::sync::Lazy;
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use tauri::{
+    AppHandle, Emitter, LogicalSize, Manager, Monitor, PhysicalPosition, WebviewWindowBuilder,
+};
 
 const OVERLAY_WIDTH: f64 = 80.0;
 const OVERLAY_HEIGHT: f64 = 18.0;
 const OVERLAY_TOP_OFFSET_PCT: f64 = 0.03;
 const OVERLAY_BOTTOM_OFFSET_PCT: f64 = 0.03;
+const MONITOR_POLL_INTERVAL: Duration = Duration::from_secs(2);
 
-fn get_primary_monitor(app_handle: &AppHandle) -> Option<tauri::Monitor> {
+// Expanded overlay: visualizer and timer row, then the transcript wrapped below
+const EXPANDED_WIDTH: f64 = 360.0;
+const EXPANDED_BASE_HEIGHT: f64 = 40.0;
+const EXPANDED_PADDING: f64 = 24.0;
+const EXPANDED_LINE_HEIGHT: f64 = 18.0;
+const EXPANDED_CHAR_WIDTH: f64 = 7.0;
+const EXPANDED_MAX_LINES: usize = 4;
+
+/// Logical size of the overlay window, which grows with the text in expanded mode.
+static OVERLAY_SIZE: Lazy<Mutex<(f64, f64)>> =
+    Lazy::new(|| Mutex::new((OVERLAY_WIDTH, OVERLAY_HEIGHT)));
+
+/// Payload of the "overlay-update" event, sent to the expanded overlay.
+#[derive(Serialize, Clone, Debug)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum OverlayUpdate {
+    /// Seconds since the recording started
+    Elapsed {
+        seconds: u64,
+    },
+    /// Rolling transcript of the end of the recording in progress
+    Partial {
+        text: String,
+    },
+    Transcribing,
+    Finished {
+        text: String,
+    },
+}
+
+/// Where the user dragged the overlay, as a fraction of the room left around it in
+/// the monitor's work area, so it stays on screen when the resolution changes.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct OverlayCustomPosition {
+    pub monitor: Option<String>,
+    pub x: f64,
+    pub y: f64,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct MonitorInfo {
+    pub name: String,
+    pub primary: bool,
+    pub width: u32,
+    pub height: u32,
+}
+
+fn get_primary_monitor(app_handle: &AppHandle) -> Option<Monitor> {
     app_handle.primary_monitor().ok().flatten()
 }
 
-fn calculate_overlay_position(app_handle: &AppHandle) -> Option<(f64, f64)> {
-    if let Some(monitor) = get_primary_monitor(app_handle) {
-        let work_area = monitor.work_area();
-        let scale = monitor.scale_factor();
-        let work_w = work_area.size.width as f64 / scale;
-        let work_h = work_area.size.height as f64 / scale;
-        let work_x = work_area.position.x as f64 / scale;
-        let work_y = work_area.position.y as f64 / scale;
-
-        let x = work_x + (work_w - OVERLAY_WIDTH) / 2.0;
-        let s = settings::load_settings(app_handle);
-        let y = match s.overlay_position.as_str() {
-            "top" => work_y + work_h * OVERLAY_TOP_OFFSET_PCT,
-            _ => work_y + work_h * (1.0 - OVERLAY_BOTTOM_OFFSET_PCT) - OVERLAY_HEIGHT,
-        };
-        return Some((x, y));
-    }
-    None
+fn find_monitor(app_handle: &AppHandle, name: &str) -> Option<Monitor> {
+    app_handle
+        .available_monitors()
+        .ok()?
+        .into_iter()
+        .find(|monitor| monitor.name().is_some_and(|n| n == name))
+}
+
+fn monitor_at(app_handle: &AppHandle, (x, y): (f64, f64)) -> Option<Monitor> {
+    app_handle.monitor_from_point(x, y).ok().flatten()
+}
+
+/// The monitor the overlay goes on, falling back to the primary one.
+fn select_monitor(app_handle: &AppHandle, s: &AppSettings) -> Option<Monitor> {
+    let monitor = if s.overlay_position.as_str() == "custom" {
+        s.overlay_custom_position
+            .as_ref()
+            .and_then(|position| position.monitor.as_deref())
+            .and_then(|name| find_monitor(app_handle, name))
+    } else {
+        match s.overlay_monitor.as_str() {
+            "primary" => None,
+            "cursor" => app_handle
+                .cursor_position()
+                .ok()
+                .and_then(|position| monitor_at(app_handle, (position.x, position.y))),
+            "focused" => {
+                focus::focused_window_center().and_then(|center| monitor_at(app_handle, center))
+            }
+            name => find_monitor(app_handle, name),
+        }
+    };
+    monitor.or_else(|| get_primary_monitor(app_handle))
+}
+
+fn calculate_overlay_position(app_handle: &AppHandle) -> Option<PhysicalPosition<i32>> {
+    let s = settings::load_settings(app_handle);
+    let monitor = select_monitor(app_handle, &s)?;
+    let work_area = monitor.work_area();
+    let scale = monitor.scale_factor();
+    let work_w = work_area.size.width as f64;
+    let work_h = work_area.size.height as f64;
+    let work_x = work_area.position.x as f64;
+    let work_y = work_area.position.y as f64;
+    let (width, height) = *OVERLAY_SIZE.lock();
+    let overlay_w = width * scale;
+    let overlay_h = height * scale;
+
+    let (x, y) = match (s.overlay_position.as_str(), &s.overlay_custom_position) {
+        ("custom", Some(position)) => (
+            work_x + (work_w - overlay_w).max(0.0) * position.x.clamp(0.0, 1.0),
+            work_y + (work_h - overlay_h).max(0.0) * position.y.clamp(0.0, 1.0),
+        ),
+        ("top", _) => (
+            work_x + (work_w - overlay_w) / 2.0,
+            work_y + work_h * OVERLAY_TOP_OFFSET_PCT,
+        ),
+        _ => (
+            work_x + (work_w - overlay_w) / 2.0,
+            work_y + work_h * (1.0 - OVERLAY_BOTTOM_OFFSET_PCT) - overlay_h,
+        ),
+    };
+    Some(PhysicalPosition::new(x.round() as i32, y.round() as i32))
 }
 
 pub fn create_recording_overlay(app_handle: &AppHandle) {
-    if let Some((x, y)) = calculate_overlay_position(app_handle) {
+    if let Some(position) = calculate_overlay_position(app_handle) {
+        let (width, height) = *OVERLAY_SIZE.lock();
         let res = WebviewWindowBuilder::new(
             app_handle,
             "recording_overlay",
             tauri::WebviewUrl::App("src/overlay/index.html".into()),
         )
         .title("Recording")
-        .position(x, y)
         .resizable(false)
-        .inner_size(OVERLAY_WIDTH, OVERLAY_HEIGHT)
+        .inner_size(width, height)
         .shadow(false)
         .maximizable(false)
         .minimizable(false)
@@ -53,10 +153,13 @@ pub fn create_recording_overlay(app_handle: &AppHandle) {
         .focused(false)
         .visible(false)
         .build();
-        if let Err(e) = res {
-            println!("Failed to create recording overlay window: {}", e);
-        } else {
-            println!("Recording overlay window created (hidden)");
+        match res {
+            Ok(window) => {
+                // Physical, since monitors may not share the same scale factor
+                let _ = window.set_position(position);
+                log::info!("Recording overlay window created (hidden)");
+            }
+            Err(e) => log::error!("Failed to create recording overlay window: {}", e),
         }
     }
 }
@@ -68,34 +171,196 @@ fn ensure_overlay(app_handle: &AppHandle) {
 }
 
 pub fn show_recording_overlay(app_handle: &AppHandle) {
-    ensure_overlay(app_handle);
+    // The monitor under the cursor or focused window may have changed since last time
+    update_overlay_position(app_handle);
     if let Some(window) = app_handle.get_webview_window("recording_overlay") {
         let _ = window.show();
         let _ = window.emit("show-overlay", "recording");
     } else {
-        println!("recording_overlay window not found on show_recording_overlay");
+        log::warn!("recording_overlay window not found on show_recording_overlay");
     }
 }
 
-// pub fn show_transcribing_overlay(app_handle: &AppHandle) {
-//     ensure_overlay(app_handle);
-//     if let Some(window) = app_handle.get_webview_window("recording_overlay") {
-//         let _ = window.show();
-//         let _ = window.emit("show-overlay", "transcribing");
-//     } else {
-//         println!("recording_overlay window not found on show_transcribing_overlay");
-//     }
-// }
+pub fn show_transcribing_overlay(app_handle: &AppHandle) {
+    ensure_overlay(app_handle);
+    if let Some(window) = app_handle.get_webview_window("recording_overlay") {
+        let _ = window.show();
+        let _ = window.emit("show-overlay", "transcribing");
+    } else {
+        log::warn!("recording_overlay window not found on show_transcribing_overlay");
+    }
+}
+
+pub fn show_cancelled_overlay(app_handle: &AppHandle) {
+    if let Some(window) = app_handle.get_webview_window("recording_overlay") {
+        let _ = window.emit("show-overlay", "cancelled");
+    } else {
+        log::warn!("recording_overlay window not found on show_cancelled_overlay");
+    }
+}
+
+/// Show the error state, with room for the message in the expanded overlay.
+pub fn show_error_overlay(app_handle: &AppHandle, message: &str) {
+    ensure_overlay(app_handle);
+    if is_expanded(&settings::load_settings(app_handle)) {
+        resize_overlay(app_handle, EXPANDED_WIDTH, expanded_height(message));
+    }
+    if let Some(window) = app_handle.get_webview_window("recording_overlay") {
+        let _ = window.show();
+        let _ = window.emit("show-overlay", "error");
+    } else {
+        log::warn!("recording_overlay window not found on show_error_overlay");
+    }
+}
 
 pub fn update_overlay_position(app_handle: &AppHandle) {
     ensure_overlay(app_handle);
-    if let Some((x, y)) = calculate_overlay_position(app_handle) {
+    if let Some(position) = calculate_overlay_position(app_handle) {
         if let Some(window) = app_handle.get_webview_window("recording_overlay") {
-            let _ = window.set_position(tauri::Position::Logical(tauri::LogicalPosition { x, y }));
+            let _ = window.set_position(position);
         }
     }
 }
 
+pub fn is_expanded(s: &AppSettings) -> bool {
+    s.overlay_style.as_str() == "expanded"
+}
+
+fn resize_overlay(app_handle: &AppHandle, width: f64, height: f64) {
+    {
+        let mut size = OVERLAY_SIZE.lock();
+        if *size == (width, height) {
+            return;
+        }
+        *size = (width, height);
+    }
+    if let Some(window) = app_handle.get_webview_window("recording_overlay") {
+        let _ = window.set_size(LogicalSize::new(width, height));
+    }
+    // Keeps the overlay anchored to its edge of the screen
+    update_overlay_position(app_handle);
+}
+
+/// Height of the expanded overlay for the text, wrapped to a few lines.
+fn expanded_height(text: &str) -> f64 {
+    let chars_per_line = ((EXPANDED_WIDTH - EXPANDED_PADDING) / EXPANDED_CHAR_WIDTH) as usize;
+    let lines = text
+        .chars()
+        .count()
+        .div_ceil(chars_per_line)
+        .min(EXPANDED_MAX_LINES);
+    EXPANDED_BASE_HEIGHT + lines as f64 * EXPANDED_LINE_HEIGHT
+}
+
+/// Back to the empty overlay of the current style, before a recording starts.
+pub fn reset_overlay_size(app_handle: &AppHandle) {
+    let s = settings::load_settings(app_handle);
+    if is_expanded(&s) {
+        resize_overlay(app_handle, EXPANDED_WIDTH, expanded_height(""));
+    } else {
+        resize_overlay(app_handle, OVERLAY_WIDTH, OVERLAY_HEIGHT);
+    }
+}
+
+/// Send an update to the expanded overlay, growing it to fit the text.
+pub fn emit_overlay_update(app_handle: &AppHandle, update: OverlayUpdate) {
+    let s = settings::load_settings(app_handle);
+    if !is_expanded(&s) {
+        return;
+    }
+    if let OverlayUpdate::Partial { text } | OverlayUpdate::Finished { text } = &update {
+        resize_overlay(app_handle, EXPANDED_WIDTH, expanded_height(text));
+    }
+    if let Some(window) = app_handle.get_webview_window("recording_overlay") {
+        let _ = window.emit("overlay-update", update);
+    }
+}
+
+/// Remember where the user dragged the overlay, relative to the monitor it is on.
+pub fn save_custom_position(app_handle: &AppHandle) -> Result<OverlayCustomPosition, String> {
+    let window = app_handle
+        .get_webview_window("recording_overlay")
+        .ok_or("Overlay window not found")?;
+    let position = window.outer_position().map_err(|e| e.to_string())?;
+    let size = window.outer_size().map_err(|e| e.to_string())?;
+    let monitor = window
+        .current_monitor()
+        .map_err(|e| e.to_string())?
+        .ok_or("The overlay is not on any monitor")?;
+    let work_area = monitor.work_area();
+
+    let fraction = |offset: i32, room: i64| -> f64 {
+        if room <= 0 {
+            return 0.5;
+        }
+        (offset as f64 / room as f64).clamp(0.0, 1.0)
+    };
+    Ok(OverlayCustomPosition {
+        monitor: monitor.name().cloned(),
+        x: fraction(
+            position.x - work_area.position.x,
+            work_area.size.width as i64 - size.width as i64,
+        ),
+        y: fraction(
+            position.y - work_area.position.y,
+            work_area.size.height as i64 - size.height as i64,
+        ),
+    })
+}
+
+pub fn list_monitors(app_handle: &AppHandle) -> Vec<MonitorInfo> {
+    let primary = get_primary_monitor(app_handle).and_then(|monitor| monitor.name().cloned());
+    app_handle
+        .available_monitors()
+        .unwrap_or_default()
+        .into_iter()
+        .filter_map(|monitor| {
+            let name = monitor.name()?.clone();
+            Some(MonitorInfo {
+                primary: primary.as_ref() == Some(&name),
+                name,
+                width: monitor.size().width,
+                height: monitor.size().height,
+            })
+        })
+        .collect()
+}
+
+/// Move the overlay back into place when monitors are plugged, unplugged or rearranged.
+pub fn watch_monitors(app_handle: &AppHandle) {
+    let app_handle = app_handle.clone();
+    std::thread::spawn(move || {
+        let layout = |app_handle: &AppHandle| -> Vec<_> {
+            app_handle
+                .available_monitors()
+                .unwrap_or_default()
+                .iter()
+                .map(|monitor| {
+                    let work_area = monitor.work_area();
+                    (
+                        monitor.name().cloned(),
+                        work_area.position.x,
+                        work_area.position.y,
+                        work_area.size.width,
+                        work_area.size.height,
+                        monitor.scale_factor().to_bits(),
+                    )
+                })
+                .collect()
+        };
+        let mut last = layout(&app_handle);
+        loop {
+            std::thread::sleep(MONITOR_POLL_INTERVAL);
+            let current = layout(&app_handle);
+            if current != last {
+                log::info!("Monitor layout changed, moving the overlay");
+                update_overlay_position(&app_handle);
+                last = current;
+            }
+        }
+    });
+}
+
 pub fn hide_recording_overlay(app_handle: &AppHandle) {
     if let Some(window) = app_handle.get_webview_window("recording_overlay") {
         let _ = window.emit("hide-overlay", ());
@@ -105,6 +370,6 @@ pub fn hide_recording_overlay(app_handle: &AppHandle) {
             let _ = win_clone.hide();
         });
     } else {
-        println!("recording_overlay window not found on hide_recording_overlay");
+        log::warn!("recording_overlay window not found on hide_recording_overlay");
     }
 }
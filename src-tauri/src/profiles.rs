@@ -0,0 +1,320 @@
+use crate::clipboard::OutputStrategy;
+use crate::focus::FocusedWindow;
+use crate::settings::AppSettings;
+use serde::{Deserialize, Serialize};
+
+/// Output behaviour applied when the focused window matches.
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[serde(default)]
+pub struct OutputProfile {
+    pub name: String,
+    /// Case-insensitive substrings of the window class
+    pub window_classes: Vec<String>,
+    /// Case-insensitive substrings of the window title
+    pub window_titles: Vec<String>,
+    /// Overrides `AppSettings::output_strategy`
+    pub output_strategy: Option<OutputStrategy>,
+    /// Overrides `AppSettings::paste_shortcut`
+    pub paste_shortcut: Option<String>,
+    /// "keep" | "lower" | "snake_case" | "camel_case"
+    pub casing: String,
+    /// "keep" | "sentence" (capital and final punctuation) | "continue" (mid-sentence:
+    /// lowercase start, no final period)
+    pub sentence_mode: String,
+    /// Applied after `sentence_mode`, so it also drops the period "sentence" adds
+    pub strip_trailing_punctuation: bool,
+    pub trailing_space: bool,
+    /// Space before the text, unless it starts with punctuation
+    pub leading_space: bool,
+    /// Rule set overrides
+    pub itn_enabled: Option<bool>,
+    pub filler_removal_enabled: Option<bool>,
+}
+
+impl OutputProfile {
+    fn matches(&self, window: &FocusedWindow) -> bool {
+        let contains = |haystack: &str, needles: &[String]| {
+            let haystack = haystack.to_lowercase();
+            needles
+                .iter()
+                .filter(|n| !n.trim().is_empty())
+                .any(|n| haystack.contains(&n.trim().to_lowercase()))
+        };
+        contains(&window.class, &self.window_classes)
+            || contains(&window.title, &self.window_titles)
+    }
+
+    /// Settings with this profile's overrides applied.
+    pub fn apply_to_settings(&self, settings: &AppSettings) -> AppSettings {
+        let mut s = settings.clone();
+        if let Some(strategy) = self.output_strategy {
+            s.output_strategy = strategy;
+        }
+        if let Some(shortcut) = &self.paste_shortcut {
+            s.paste_shortcut = shortcut.clone();
+        }
+        if let Some(enabled) = self.itn_enabled {
+            s.itn_enabled = enabled;
+        }
+        if let Some(enabled) = self.filler_removal_enabled {
+            s.filler_removal_enabled = enabled;
+        }
+        s
+    }
+
+    pub fn format_text(&self, text: &str) -> String {
+        let mut text = text.trim().to_string();
+
+        text = match self.sentence_mode.as_str() {
+            "sentence" => sentence_case(&text),
+            "continue" => continue_sentence(&text),
+            _ => text,
+        };
+
+        // After the sentence mode, so that the period it adds is stripped too
+        if self.strip_trailing_punctuation {
+            let trimmed = text.trim_end_matches(['.', '!', '?', '。']).len();
+            text.truncate(trimmed);
+        }
+
+        text = match self.casing.as_str() {
+            "lower" => text.to_lowercase(),
+            "snake_case" => identifier_words(&text).join("_"),
+            "camel_case" => identifier_words(&text)
+                .iter()
+                .enumerate()
+                .map(|(i, w)| if i == 0 { w.clone() } else { capitalize(w) })
+                .collect(),
+            _ => text,
+        };
+
+        if self.trailing_space && !text.is_empty() {
+            text.push(' ');
+        }
+        // ", and then" sticks to the previous word
+        let attaches = text.starts_with(|c: char| ",.;:!?)]}…".contains(c));
+        if self.leading_space && !text.is_empty() && !attaches {
+            text.insert(0, ' ');
+        }
+        text
+    }
+}
+
+pub fn default_output_profiles() -> Vec<OutputProfile> {
+    vec![OutputProfile {
+        name: "Terminal".to_string(),
+        window_classes: [
+            "gnome-terminal",
+            "konsole",
+            "xterm",
+            "alacritty",
+            "kitty",
+            "wezterm",
+            "terminator",
+            "tilix",
+            "xfce4-terminal",
+        ]
+        .iter()
+        .map(|c| c.to_string())
+        .collect(),
+        paste_shortcut: Some("ctrl+shift+v".to_string()),
+        casing: "keep".to_string(),
+        ..Default::default()
+    }]
+}
+
+/// The pinned profile, or else the first one matching the currently focused window.
+pub fn active_profile(settings: &AppSettings) -> Option<OutputProfile> {
+    if settings.output_profiles.is_empty() {
+        return None;
+    }
+    if let Some(name) = &settings.pinned_output_profile {
+        let pinned = settings
+            .output_profiles
+            .iter()
+            .find(|p| p.name.eq_ignore_ascii_case(name))
+            .cloned();
+        if pinned.is_some() {
+            return pinned;
+        }
+    }
+    let window = crate::focus::focused_window()?;
+    let profile = settings
+        .output_profiles
+        .iter()
+        .find(|p| p.matches(&window))
+        .cloned();
+    if let Some(ref p) = profile {
+        log::info!(
+            "Output profile '{}' matched window '{}' ({})",
+            p.name,
+            window.title,
+            window.class
+        );
+    }
+    profile
+}
+
+fn identifier_words(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .map(|w| w.to_lowercase())
+        .collect()
+}
+
+fn ends_sentence(text: &str) -> bool {
+    text.trim_end_matches(['"', '\'', ')', '»', '”'])
+        .ends_with(['.', '!', '?', '…', '。'])
+}
+
+/// Capital first letter and final punctuation, for short utterances the model left bare.
+fn sentence_case(text: &str) -> String {
+    let mut text = capitalize(text);
+    if !text.is_empty() && !ends_sentence(&text) && !text.ends_with([',', ';', ':']) {
+        text.push('.');
+    }
+    text
+}
+
+/// Text that goes on a sentence already started: lowercase first letter, no final period.
+/// Acronyms and "I" keep their capitals.
+fn continue_sentence(text: &str) -> String {
+    let text = text
+        .strip_suffix('.')
+        .filter(|t| !t.ends_with('.'))
+        .unwrap_or(text);
+    let first_word = text.split_whitespace().next().unwrap_or("");
+    let letters: Vec<char> = first_word.chars().filter(|c| c.is_alphabetic()).collect();
+    // "A new idea" starts with a capital, not an acronym
+    let acronym = letters.len() >= 2 && letters.iter().all(|c| c.is_uppercase());
+    // "I", "I'm", "I'll"
+    let pronoun_i = first_word.split(['\'', '’']).next() == Some("I");
+    if acronym || pronoun_i {
+        return text.to_string();
+    }
+    let mut chars = text.chars();
+    match chars.next() {
+        Some(first) => first.to_lowercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::OutputProfile;
+
+    fn check(profile: &OutputProfile, cases: &[(&str, &str)]) {
+        for (input, expected) in cases {
+            assert_eq!(profile.format_text(input), *expected, "input: {:?}", input);
+        }
+    }
+
+    fn profile(sentence_mode: &str) -> OutputProfile {
+        OutputProfile {
+            sentence_mode: sentence_mode.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn keep() {
+        check(
+            &profile("keep"),
+            &[
+                ("  Hello world.  ", "Hello world."),
+                ("hello world", "hello world"),
+                ("", ""),
+            ],
+        );
+    }
+
+    #[test]
+    fn sentence() {
+        check(
+            &profile("sentence"),
+            &[
+                ("hello world", "Hello world."),
+                ("is it done?", "Is it done?"),
+                ("he said \"stop.\"", "He said \"stop.\""),
+                ("first,", "First,"),
+                ("élan", "Élan."),
+            ],
+        );
+    }
+
+    #[test]
+    fn continue_acronyms_and_i() {
+        check(
+            &profile("continue"),
+            &[
+                ("Then we left.", "then we left"),
+                ("A new idea", "a new idea"),
+                ("NASA said so.", "NASA said so"),
+                ("USB-C works", "USB-C works"),
+                ("I think so.", "I think so"),
+                ("I'm here", "I'm here"),
+                ("I’ll go", "I’ll go"),
+                ("I've seen it", "I've seen it"),
+                ("It works", "it works"),
+                ("Wait...", "wait..."),
+            ],
+        );
+    }
+
+    #[test]
+    fn trailing_punctuation_stripping() {
+        let strip = |sentence_mode| OutputProfile {
+            strip_trailing_punctuation: true,
+            ..profile(sentence_mode)
+        };
+        check(
+            &strip("keep"),
+            &[
+                ("Done.", "Done"),
+                ("Really?!", "Really"),
+                ("完成。", "完成"),
+                ("a, b,", "a, b,"),
+            ],
+        );
+        // Also drops the period the sentence mode adds
+        check(&strip("sentence"), &[("hello", "Hello"), ("Hi.", "Hi")]);
+    }
+
+    #[test]
+    fn leading_and_trailing_space() {
+        let spaced = OutputProfile {
+            leading_space: true,
+            trailing_space: true,
+            ..profile("keep")
+        };
+        check(
+            &spaced,
+            &[
+                ("hello", " hello "),
+                (", and then", ", and then "),
+                (". Next", ". Next "),
+                ("(aside)", " (aside) "),
+                ("", ""),
+            ],
+        );
+    }
+
+    #[test]
+    fn casing() {
+        let cased = |casing: &str| OutputProfile {
+            casing: casing.to_string(),
+            ..profile("keep")
+        };
+        check(&cased("lower"), &[("Hello World", "hello world")]);
+        check(&cased("snake_case"), &[("Open the file", "open_the_file")]);
+        check(&cased("camel_case"), &[("open the file", "openTheFile")]);
+    }
+}
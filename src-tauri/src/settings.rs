@@ -1,5 +1,12 @@
+use crate::clipboard::OutputStrategy;
+use crate::disfluency::{default_filler_words, FillerWords};
+use crate::engine::ENGINES;
+use crate::model::MODEL_FILENAME;
+use crate::overlay::OverlayCustomPosition;
+use crate::profiles::{default_output_profiles, OutputProfile};
+use crate::shortcuts::ShortcutBinding;
 use serde::{Deserialize, Serialize};
-use std::{fs, path::PathBuf};
+use std::{collections::HashMap, fs, path::PathBuf};
 use tauri::{AppHandle, Manager};
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
@@ -9,9 +16,70 @@ pub struct AppSettings {
     pub last_transcript_shortcut: String,
     pub dictionary: Vec<String>,
     pub overlay_mode: String,     // "hidden" | "recording" | "always"
-    pub overlay_position: String, // "top" | "bottom"
+    pub overlay_position: String, // "top" | "bottom" | "custom"
+    pub overlay_monitor: String,  // "primary" | "cursor" | "focused" | monitor name
+    pub overlay_custom_position: Option<OverlayCustomPosition>, // Set by dragging the overlay
+    pub overlay_style: String,    // "compact" | "expanded" (live transcript and timer)
     pub api_enabled: bool,        // Enable local HTTP API
     pub api_port: u16,            // Port for local HTTP API
+    pub itn_enabled: bool,        // Write numbers, dates and units in digits
+    pub itn_language: String,     // "en" | "fr"
+    pub filler_removal_enabled: bool,
+    pub filler_words: HashMap<String, FillerWords>, // language code -> filler words
+    pub output_strategy: OutputStrategy,            // How the text reaches the focused app
+    pub paste_shortcut: String,                     // Chord sent by the "paste" strategy, not macOS
+    pub output_profiles: Vec<OutputProfile>,        // Per-application overrides, first match wins
+    pub record_mode: String,                        // "push_to_talk" | "toggle" | "hybrid"
+    pub max_recording_seconds: u64,                 // Recording safety cutoff, 0 for no limit
+    pub shortcut_match_mode: String,                // "exact" | "superset"
+    pub custom_shortcuts: Vec<ShortcutBinding>,     // Extra chords bound to actions
+    pub pinned_output_profile: Option<String>,      // Used instead of window matching when set
+    pub linux_input_backend: String,                // "auto" | "x11" | "evdev" | "portal"
+    pub microphone: Option<String>, // Input device name, the system default when unset
+    pub active_model: String,       // Name of an installed model
+    pub model_source: String,       // Base URL serving manifest.json and the model archives
+    pub engine: String,             // "parakeet" | "ctc", must fit the active model
+    pub engine_params: HashMap<String, EngineParams>, // engine -> how it loads models
+    pub model_idle_unload_minutes: u64, // Unload the model when unused this long, 0 never
+    pub prewarm_model_on_record: bool, // Reload an unloaded model as soon as recording starts
+    pub dictionary_skip_confidence: f32, // Words this confident skip the dictionary, 0 checks all
+    pub language_detection_enabled: bool, // Dictionary, ITN and fillers follow the detected language
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(default)]
+pub struct EngineParams {
+    pub precision: String, // "int8" | "fp32"
+    pub onnx_runtime: OnnxRuntimeSettings,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(default)]
+pub struct OnnxRuntimeSettings {
+    pub intra_threads: usize,       // 0 lets ONNX Runtime decide
+    pub inter_threads: usize,       // 0 lets ONNX Runtime decide
+    pub optimization_level: String, // "disable" | "basic" | "extended" | "all"
+    pub save_optimized_model: bool, // Reuse the optimized graph on the next startup
+}
+
+impl Default for EngineParams {
+    fn default() -> Self {
+        Self {
+            precision: "int8".to_string(),
+            onnx_runtime: OnnxRuntimeSettings::default(),
+        }
+    }
+}
+
+impl Default for OnnxRuntimeSettings {
+    fn default() -> Self {
+        Self {
+            intra_threads: 0,
+            inter_threads: 0,
+            optimization_level: "all".to_string(),
+            save_optimized_model: false,
+        }
+    }
 }
 
 impl Default for AppSettings {
@@ -22,12 +90,54 @@ impl Default for AppSettings {
             dictionary: Vec::new(),
             overlay_mode: "recording".to_string(),
             overlay_position: "bottom".to_string(),
+            overlay_monitor: "primary".to_string(),
+            overlay_custom_position: None,
+            overlay_style: "compact".to_string(),
             api_enabled: false,
             api_port: 4800,
+            itn_enabled: false,
+            itn_language: "en".to_string(),
+            filler_removal_enabled: false,
+            filler_words: default_filler_words(),
+            output_strategy: OutputStrategy::Paste,
+            paste_shortcut: "ctrl+v".to_string(),
+            output_profiles: default_output_profiles(),
+            record_mode: "push_to_talk".to_string(),
+            max_recording_seconds: 300,
+            shortcut_match_mode: "exact".to_string(),
+            custom_shortcuts: Vec::new(),
+            pinned_output_profile: None,
+            linux_input_backend: "auto".to_string(),
+            microphone: None,
+            active_model: MODEL_FILENAME.to_string(),
+            model_source: String::new(),
+            engine: "parakeet".to_string(),
+            engine_params: ENGINES
+                .iter()
+                .map(|engine| (engine.to_string(), EngineParams::default()))
+                .collect(),
+            model_idle_unload_minutes: 0,
+            prewarm_model_on_record: true,
+            dictionary_skip_confidence: 0.0,
+            language_detection_enabled: false,
         }
     }
 }
 
+impl AppSettings {
+    /// How the selected engine loads models.
+    pub fn engine_params(&self) -> EngineParams {
+        self.engine_params
+            .get(&self.engine)
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    pub fn engine_params_mut(&mut self) -> &mut EngineParams {
+        self.engine_params.entry(self.engine.clone()).or_default()
+    }
+}
+
 fn settings_path(app: &AppHandle) -> Result<PathBuf, String> {
     let dir = app.path().app_data_dir().map_err(|e| e.to_string())?;
     if let Err(e) = fs::create_dir_all(&dir) {
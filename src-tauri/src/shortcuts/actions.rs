@@ -0,0 +1,139 @@
+use crate::audio::{retranscribe_last, write_transcription};
+use crate::error;
+use crate::history::get_last_transcription;
+use crate::settings;
+use crate::shortcuts::TranscriptionSuspended;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, Manager};
+
+/// A user-defined shortcut as stored in settings.
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[serde(default)]
+pub struct ShortcutBinding {
+    /// One of `ShortcutAction::NAMES`
+    pub action: String,
+    pub binding: String,
+    /// Language code for "switch_language", profile name for "switch_profile".
+    /// Without it, languages are cycled and the profile goes back to automatic.
+    pub argument: Option<String>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum ShortcutAction {
+    Record,
+    PasteLastTranscript,
+    CancelRecording,
+    ToggleSuspend,
+    RetranscribeLast,
+    SwitchLanguage(Option<String>),
+    SwitchProfile(Option<String>),
+    OpenHistory,
+}
+
+const LANGUAGES: [&str; 2] = ["en", "fr"];
+
+impl ShortcutAction {
+    pub const NAMES: [&'static str; 8] = [
+        "record",
+        "paste_last_transcript",
+        "cancel_recording",
+        "toggle_suspend",
+        "retranscribe_last",
+        "switch_language",
+        "switch_profile",
+        "open_history",
+    ];
+
+    pub fn parse(name: &str, argument: Option<&str>) -> Option<Self> {
+        let argument = argument
+            .map(str::trim)
+            .filter(|a| !a.is_empty())
+            .map(str::to_string);
+        match name {
+            "record" => Some(Self::Record),
+            "paste_last_transcript" => Some(Self::PasteLastTranscript),
+            "cancel_recording" => Some(Self::CancelRecording),
+            "toggle_suspend" => Some(Self::ToggleSuspend),
+            "retranscribe_last" => Some(Self::RetranscribeLast),
+            "switch_language" => match argument {
+                Some(language) if !LANGUAGES.contains(&language.as_str()) => None,
+                argument => Some(Self::SwitchLanguage(argument)),
+            },
+            "switch_profile" => Some(Self::SwitchProfile(argument)),
+            "open_history" => Some(Self::OpenHistory),
+            _ => None,
+        }
+    }
+
+    /// Whether the action only makes sense during a recording, so its keys
+    /// should not be taken from other applications the rest of the time.
+    pub fn is_recording_only(&self) -> bool {
+        matches!(self, Self::CancelRecording)
+    }
+
+    /// Whether the action still runs while transcription is suspended.
+    pub fn runs_when_suspended(&self) -> bool {
+        matches!(
+            self,
+            Self::CancelRecording
+                | Self::ToggleSuspend
+                | Self::SwitchLanguage(_)
+                | Self::SwitchProfile(_)
+                | Self::OpenHistory
+        )
+    }
+}
+
+/// Run an action on press. Recording and cancelling are driven by
+/// `RecordShortcutHandler` instead.
+pub fn run_action(app: &AppHandle, action: &ShortcutAction) {
+    log::info!("Shortcut action: {:?}", action);
+    match action {
+        ShortcutAction::Record | ShortcutAction::CancelRecording => {}
+        ShortcutAction::PasteLastTranscript => {
+            if let Ok(last_transcript) = get_last_transcription(app) {
+                if let Err(e) = write_transcription(app, &last_transcript) {
+                    error::report(app, &e);
+                }
+            }
+        }
+        ShortcutAction::ToggleSuspend => {
+            let suspended = app.state::<TranscriptionSuspended>();
+            suspended.set(!suspended.get());
+            let _ = app.emit("transcription-suspended", suspended.get());
+        }
+        ShortcutAction::RetranscribeLast => retranscribe_last(app),
+        ShortcutAction::SwitchLanguage(language) => {
+            let mut s = settings::load_settings(app);
+            s.itn_language = match language {
+                Some(language) => language.clone(),
+                None => {
+                    let current = LANGUAGES.iter().position(|l| *l == s.itn_language);
+                    let next = current.map_or(0, |i| (i + 1) % LANGUAGES.len());
+                    LANGUAGES[next].to_string()
+                }
+            };
+            if let Err(e) = settings::save_settings(app, &s) {
+                log::error!("Failed to switch language: {}", e);
+                return;
+            }
+            let _ = app.emit("language-changed", s.itn_language);
+        }
+        ShortcutAction::SwitchProfile(profile) => {
+            let mut s = settings::load_settings(app);
+            s.pinned_output_profile = profile.clone();
+            if let Err(e) = settings::save_settings(app, &s) {
+                log::error!("Failed to switch output profile: {}", e);
+                return;
+            }
+            let _ = app.emit("output-profile-changed", profile);
+        }
+        ShortcutAction::OpenHistory => {
+            if let Some(window) = app.get_webview_window("main") {
+                let _ = window.show();
+                let _ = window.set_focus();
+            }
+            let _ = app.emit("open-history", ());
+        }
+    }
+}
@@ -0,0 +1,241 @@
+use crate::shortcuts::KeyEvent;
+use evdev::uinput::VirtualDevice;
+use evdev::{AttributeSet, Device, EventSummary, EventType, InputEvent, KeyCode};
+use once_cell::sync::Lazy;
+use parking_lot::Mutex;
+use std::collections::HashSet;
+use std::path::PathBuf;
+use std::sync::mpsc::Sender;
+use std::sync::Arc;
+use std::time::Duration;
+
+const VIRTUAL_KEYBOARD_NAME: &str = "Murmure virtual keyboard";
+/// New devices (docking station, USB keyboard) are picked up at this interval.
+const RESCAN_INTERVAL: Duration = Duration::from_secs(5);
+
+static VIRTUAL_KEYBOARD: Lazy<Mutex<Option<VirtualDevice>>> = Lazy::new(|| Mutex::new(None));
+
+const KEYS: [(KeyCode, i32); 71] = [
+    (KeyCode::KEY_LEFTMETA, 0x5B),
+    (KeyCode::KEY_RIGHTMETA, 0x5C),
+    (KeyCode::KEY_LEFTCTRL, 0xA2),
+    (KeyCode::KEY_RIGHTCTRL, 0xA3),
+    (KeyCode::KEY_LEFTALT, 0xA4),
+    (KeyCode::KEY_RIGHTALT, 0xA5),
+    (KeyCode::KEY_LEFTSHIFT, 0xA0),
+    (KeyCode::KEY_RIGHTSHIFT, 0xA1),
+    (KeyCode::KEY_A, 0x41),
+    (KeyCode::KEY_B, 0x42),
+    (KeyCode::KEY_C, 0x43),
+    (KeyCode::KEY_D, 0x44),
+    (KeyCode::KEY_E, 0x45),
+    (KeyCode::KEY_F, 0x46),
+    (KeyCode::KEY_G, 0x47),
+    (KeyCode::KEY_H, 0x48),
+    (KeyCode::KEY_I, 0x49),
+    (KeyCode::KEY_J, 0x4A),
+    (KeyCode::KEY_K, 0x4B),
+    (KeyCode::KEY_L, 0x4C),
+    (KeyCode::KEY_M, 0x4D),
+    (KeyCode::KEY_N, 0x4E),
+    (KeyCode::KEY_O, 0x4F),
+    (KeyCode::KEY_P, 0x50),
+    (KeyCode::KEY_Q, 0x51),
+    (KeyCode::KEY_R, 0x52),
+    (KeyCode::KEY_S, 0x53),
+    (KeyCode::KEY_T, 0x54),
+    (KeyCode::KEY_U, 0x55),
+    (KeyCode::KEY_V, 0x56),
+    (KeyCode::KEY_W, 0x57),
+    (KeyCode::KEY_X, 0x58),
+    (KeyCode::KEY_Y, 0x59),
+    (KeyCode::KEY_Z, 0x5A),
+    (KeyCode::KEY_0, 0x30),
+    (KeyCode::KEY_1, 0x31),
+    (KeyCode::KEY_2, 0x32),
+    (KeyCode::KEY_3, 0x33),
+    (KeyCode::KEY_4, 0x34),
+    (KeyCode::KEY_5, 0x35),
+    (KeyCode::KEY_6, 0x36),
+    (KeyCode::KEY_7, 0x37),
+    (KeyCode::KEY_8, 0x38),
+    (KeyCode::KEY_9, 0x39),
+    (KeyCode::KEY_F1, 0x70),
+    (KeyCode::KEY_F2, 0x71),
+    (KeyCode::KEY_F3, 0x72),
+    (KeyCode::KEY_F4, 0x73),
+    (KeyCode::KEY_F5, 0x74),
+    (KeyCode::KEY_F6, 0x75),
+    (KeyCode::KEY_F7, 0x76),
+    (KeyCode::KEY_F8, 0x77),
+    (KeyCode::KEY_F9, 0x78),
+    (KeyCode::KEY_F10, 0x79),
+    (KeyCode::KEY_F11, 0x7A),
+    (KeyCode::KEY_F12, 0x7B),
+    (KeyCode::KEY_SPACE, 0x20),
+    (KeyCode::KEY_ENTER, 0x0D),
+    (KeyCode::KEY_ESC, 0x1B),
+    (KeyCode::KEY_TAB, 0x09),
+    (KeyCode::KEY_BACKSPACE, 0x08),
+    (KeyCode::KEY_DELETE, 0x2E),
+    (KeyCode::KEY_INSERT, 0x2D),
+    (KeyCode::KEY_HOME, 0x24),
+    (KeyCode::KEY_END, 0x23),
+    (KeyCode::KEY_PAGEUP, 0x21),
+    (KeyCode::KEY_PAGEDOWN, 0x22),
+    (KeyCode::KEY_UP, 0x26),
+    (KeyCode::KEY_DOWN, 0x28),
+    (KeyCode::KEY_LEFT, 0x25),
+    (KeyCode::KEY_RIGHT, 0x27),
+];
+
+/// BTN_SIDE and BTN_EXTRA are the back and forward side buttons.
+const BUTTONS: [(KeyCode, i32); 3] = [
+    (KeyCode::BTN_MIDDLE, 0x04),
+    (KeyCode::BTN_SIDE, 0x05),
+    (KeyCode::BTN_EXTRA, 0x06),
+];
+
+fn key_code_to_vk(code: KeyCode) -> Option<i32> {
+    KEYS.iter()
+        .chain(BUTTONS.iter())
+        .find(|(c, _)| *c == code)
+        .map(|(_, vk)| *vk)
+}
+
+/// Inverse of `key_code_to_vk`. Generic modifiers map to their left-hand key.
+fn vk_to_key_code(vk: i32) -> Option<KeyCode> {
+    let vk = match vk {
+        0x10 => 0xA0,
+        0x11 => 0xA2,
+        0x12 => 0xA4,
+        vk => vk,
+    };
+    KEYS.iter().find(|(_, v)| *v == vk).map(|(c, _)| *c)
+}
+
+fn is_keyboard(device: &Device) -> bool {
+    device.name() != Some(VIRTUAL_KEYBOARD_NAME)
+        && device
+            .supported_keys()
+            .is_some_and(|keys| keys.contains(KeyCode::KEY_SPACE) && keys.contains(KeyCode::KEY_A))
+}
+
+fn is_mouse(device: &Device) -> bool {
+    device.supported_keys().is_some_and(|keys| {
+        keys.contains(KeyCode::BTN_LEFT) && BUTTONS.iter().any(|(code, _)| keys.contains(*code))
+    })
+}
+
+/// Whether at least one keyboard under /dev/input can be read, which usually
+/// requires membership of the `input` group.
+pub fn keyboards_available() -> bool {
+    evdev::enumerate().any(|(_, device)| is_keyboard(&device))
+}
+
+/// Whether /dev/uinput can be opened to inject keys.
+pub fn uinput_available() -> bool {
+    std::fs::OpenOptions::new()
+        .write(true)
+        .open("/dev/uinput")
+        .is_ok()
+}
+
+/// Read key and mouse button presses and releases from every keyboard and
+/// mouse under /dev/input. Works on X11 and Wayland alike.
+pub fn listen_keys(sender: Sender<KeyEvent>) {
+    let watched: Arc<Mutex<HashSet<PathBuf>>> = Arc::new(Mutex::new(HashSet::new()));
+
+    std::thread::spawn(move || loop {
+        for (path, device) in evdev::enumerate() {
+            let watch = is_keyboard(&device) || is_mouse(&device);
+            if !watch || !watched.lock().insert(path.clone()) {
+                continue;
+            }
+            log::info!(
+                "Listening to input device {} ({})",
+                device.name().unwrap_or("unknown"),
+                path.display()
+            );
+            let sender = sender.clone();
+            let watched = watched.clone();
+            std::thread::spawn(move || {
+                read_device(device, &sender);
+                // Unplugged: allow the device to be picked up again
+                watched.lock().remove(&path);
+            });
+        }
+        std::thread::sleep(RESCAN_INTERVAL);
+    });
+}
+
+fn read_device(mut device: Device, sender: &Sender<KeyEvent>) {
+    loop {
+        let events = match device.fetch_events() {
+            Ok(events) => events,
+            Err(_) => return,
+        };
+        for event in events {
+            // 0 is a release, 1 a press and 2 an auto-repeat
+            let EventSummary::Key(_, code, value) = event.destructure() else {
+                continue;
+            };
+            let key_event = match (key_code_to_vk(code), value) {
+                (Some(vk), 1) => KeyEvent::Down(vk),
+                (Some(vk), 0) => KeyEvent::Up(vk),
+                _ => continue,
+            };
+            if sender.send(key_event).is_err() {
+                return;
+            }
+        }
+    }
+}
+
+fn create_virtual_keyboard() -> std::io::Result<VirtualDevice> {
+    let mut keys = AttributeSet::<KeyCode>::new();
+    for (code, _) in KEYS {
+        keys.insert(code);
+    }
+
+    let device = VirtualDevice::builder()?
+        .name(VIRTUAL_KEYBOARD_NAME)
+        .with_keys(&keys)?
+        .build()?;
+    // Give the compositor time to pick up the new device
+    std::thread::sleep(Duration::from_millis(200));
+    Ok(device)
+}
+
+/// Press the keys in order and release them in reverse order through a
+/// virtual uinput keyboard.
+pub fn send_keys(keys: &[i32]) -> Result<(), String> {
+    let codes: Vec<KeyCode> = keys.iter().filter_map(|&vk| vk_to_key_code(vk)).collect();
+    if codes.is_empty() {
+        return Err("Invalid paste shortcut".to_string());
+    }
+
+    let mut keyboard = VIRTUAL_KEYBOARD.lock();
+    if keyboard.is_none() {
+        *keyboard = Some(
+            create_virtual_keyboard()
+                .map_err(|e| format!("Failed to create virtual keyboard: {}", e))?,
+        );
+    }
+    let keyboard = keyboard.as_mut().unwrap();
+
+    let key = |code: KeyCode, value| InputEvent::new(EventType::KEY.0, code.0, value);
+    for code in &codes {
+        keyboard
+            .emit(&[key(*code, 1)])
+            .map_err(|e| format!("Failed to press {:?}: {}", code, e))?;
+        std::thread::sleep(Duration::from_millis(10));
+    }
+    for code in codes.iter().rev() {
+        keyboard
+            .emit(&[key(*code, 0)])
+            .map_err(|e| format!("Failed to release {:?}: {}", code, e))?;
+        std::thread::sleep(Duration::from_millis(10));
+    }
+    Ok(())
+}
@@ -0,0 +1,106 @@
+use crate::settings;
+use crate::shortcuts::KeyEvent;
+use once_cell::sync::OnceCell;
+use std::sync::mpsc::Sender;
+use tauri::AppHandle;
+
+mod evdev;
+mod portal;
+mod x11;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum InputBackend {
+    /// rdev through the X server, XWayland windows only on Wayland
+    X11,
+    /// /dev/input and /dev/uinput, needs the `input` group
+    Evdev,
+    /// XDG GlobalShortcuts portal, listening only
+    Portal,
+}
+
+impl InputBackend {
+    /// `None` for "auto".
+    pub fn parse(backend: &str) -> Option<Self> {
+        match backend {
+            "x11" => Some(Self::X11),
+            "evdev" => Some(Self::Evdev),
+            "portal" => Some(Self::Portal),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::X11 => "x11",
+            Self::Evdev => "evdev",
+            Self::Portal => "portal",
+        }
+    }
+}
+
+static LISTEN_BACKEND: OnceCell<InputBackend> = OnceCell::new();
+
+pub fn is_wayland_session() -> bool {
+    std::env::var("XDG_SESSION_TYPE").is_ok_and(|t| t.eq_ignore_ascii_case("wayland"))
+        || std::env::var_os("WAYLAND_DISPLAY").is_some()
+}
+
+/// The backend used to listen for shortcuts. X11 sessions keep rdev. On Wayland,
+/// reading /dev/input is preferred since it sees every key press and release,
+/// the portal is used when the keyboards are not readable.
+fn select_backend(app: &AppHandle) -> InputBackend {
+    let setting = settings::load_settings(app).linux_input_backend;
+    if let Some(backend) = InputBackend::parse(&setting) {
+        return backend;
+    }
+    if !is_wayland_session() {
+        InputBackend::X11
+    } else if evdev::keyboards_available() {
+        InputBackend::Evdev
+    } else {
+        InputBackend::Portal
+    }
+}
+
+/// The backend shortcuts are listened with, once started.
+pub fn active_backend() -> Option<InputBackend> {
+    LISTEN_BACKEND.get().copied()
+}
+
+pub fn listen_keys(app: &AppHandle, sender: Sender<KeyEvent>) {
+    let backend = select_backend(app);
+    let _ = LISTEN_BACKEND.set(backend);
+    log::info!(
+        "Listening for shortcuts with the {} backend",
+        backend.name()
+    );
+
+    match backend {
+        InputBackend::X11 => x11::listen_keys(sender),
+        InputBackend::Evdev => evdev::listen_keys(sender),
+        InputBackend::Portal => {
+            let app = app.clone();
+            tauri::async_runtime::spawn(async move {
+                if let Err(e) = portal::listen_keys(app, sender.clone()).await {
+                    log::warn!("GlobalShortcuts portal unavailable, using X11: {}", e);
+                    x11::listen_keys(sender);
+                }
+            });
+        }
+    }
+}
+
+/// Press and release the keys with uinput on Wayland when it is writable,
+/// through XTest otherwise.
+pub fn send_keys(keys: &[i32]) -> Result<(), String> {
+    let use_uinput = match active_backend() {
+        Some(InputBackend::Evdev) => true,
+        Some(InputBackend::X11) => false,
+        _ => is_wayland_session(),
+    };
+    if use_uinput && evdev::uinput_available() {
+        evdev::send_keys(keys)
+    } else {
+        x11::send_keys(keys)
+    }
+}
@@ -0,0 +1,126 @@
+use crate::shortcuts::{current_bindings, KeyEvent, ShortcutAction};
+use ashpd::desktop::global_shortcuts::{GlobalShortcuts, NewShortcut};
+use futures_util::StreamExt;
+use std::sync::mpsc::Sender;
+use tauri::AppHandle;
+
+/// XDG trigger for a key, using the xkb keysym names. Triggers cannot tell
+/// left from right modifiers, nor express mouse buttons or double-taps.
+fn vk_to_trigger_key(vk: i32) -> Option<String> {
+    let name = match vk {
+        0x5B | 0x5C => "LOGO",
+        0x11 | 0xA2 | 0xA3 => "CTRL",
+        0x12 | 0xA4 | 0xA5 => "ALT",
+        0x10 | 0xA0 | 0xA1 => "SHIFT",
+        0x41..=0x5A | 0x30..=0x39 => {
+            return Some((vk as u8 as char).to_ascii_lowercase().to_string())
+        }
+        0x70..=0x7B => return Some(format!("F{}", vk - 0x6F)),
+        0x20 => "space",
+        0x0D => "Return",
+        0x1B => "Escape",
+        0x09 => "Tab",
+        0x08 => "BackSpace",
+        0x2E => "Delete",
+        0x2D => "Insert",
+        0x24 => "Home",
+        0x23 => "End",
+        0x21 => "Page_Up",
+        0x22 => "Page_Down",
+        0x26 => "Up",
+        0x28 => "Down",
+        0x25 => "Left",
+        0x27 => "Right",
+        _ => return None,
+    };
+    Some(name.to_string())
+}
+
+/// Preferred trigger such as "CTRL+ALT+space", modifiers first.
+fn preferred_trigger(keys: &[i32]) -> Option<String> {
+    let is_modifier = |vk: &i32| matches!(vk, 0x5B | 0x5C | 0x10..=0x12 | 0xA0..=0xA5);
+    let ordered = keys
+        .iter()
+        .filter(|vk| is_modifier(vk))
+        .chain(keys.iter().filter(|vk| !is_modifier(vk)));
+    let parts: Option<Vec<String>> = ordered.map(|&vk| vk_to_trigger_key(vk)).collect();
+    parts.map(|p| p.join("+"))
+}
+
+fn description(action: &ShortcutAction) -> String {
+    match action {
+        ShortcutAction::Record => "Record".to_string(),
+        ShortcutAction::PasteLastTranscript => "Paste last transcript".to_string(),
+        ShortcutAction::CancelRecording => "Cancel recording".to_string(),
+        ShortcutAction::ToggleSuspend => "Suspend or resume transcription".to_string(),
+        ShortcutAction::RetranscribeLast => "Re-transcribe last recording".to_string(),
+        ShortcutAction::SwitchLanguage(Some(language)) => {
+            format!("Switch language to {}", language)
+        }
+        ShortcutAction::SwitchLanguage(None) => "Switch language".to_string(),
+        ShortcutAction::SwitchProfile(Some(profile)) => format!("Use output profile {}", profile),
+        ShortcutAction::SwitchProfile(None) => "Use automatic output profile".to_string(),
+        ShortcutAction::OpenHistory => "Open history".to_string(),
+    }
+}
+
+/// Register the shortcuts with the desktop through the XDG GlobalShortcuts
+/// portal. The desktop owns the key grab, so activations are turned back into
+/// presses and releases of the binding's keys for the shortcut engine, a
+/// double-tap binding being reported as already double-tapped.
+/// Recording-only bindings are left out, since the desktop would grab their
+/// keys (escape) for good. Bindings changed later only reach the desktop after
+/// a restart.
+pub async fn listen_keys(app: AppHandle, sender: Sender<KeyEvent>) -> ashpd::Result<()> {
+    let bindings = current_bindings(&app);
+    let shortcuts: Vec<NewShortcut> = bindings
+        .iter()
+        .enumerate()
+        .filter(|(_, (action, _))| !action.is_recording_only())
+        .map(|(i, (action, keys))| {
+            NewShortcut::new(format!("murmure-{}", i), description(action))
+                .preferred_trigger(preferred_trigger(keys).as_deref())
+        })
+        .collect();
+
+    let portal = GlobalShortcuts::new().await?;
+    let session = portal.create_session().await?;
+    let bound = portal
+        .bind_shortcuts(&session, &shortcuts, None)
+        .await?
+        .response()?;
+    for shortcut in bound.shortcuts() {
+        log::info!(
+            "Global shortcut '{}' bound to {}",
+            shortcut.description(),
+            shortcut.trigger_description()
+        );
+    }
+
+    let keys_for = |id: &str| {
+        id.strip_prefix("murmure-")
+            .and_then(|i| i.parse::<usize>().ok())
+            .and_then(|i| bindings.get(i))
+            .map(|(_, keys)| keys.clone())
+            .unwrap_or_default()
+    };
+
+    let mut activated = portal.receive_activated().await?;
+    let mut deactivated = portal.receive_deactivated().await?;
+    loop {
+        let events: Vec<KeyEvent> = tokio::select! {
+            Some(signal) = activated.next() => {
+                keys_for(signal.shortcut_id()).into_iter().map(KeyEvent::Down).collect()
+            }
+            Some(signal) = deactivated.next() => {
+                keys_for(signal.shortcut_id()).into_iter().rev().map(KeyEvent::Up).collect()
+            }
+            else => return Ok(()),
+        };
+        for event in events {
+            if sender.send(event).is_err() {
+                return Ok(());
+            }
+        }
+    }
+}
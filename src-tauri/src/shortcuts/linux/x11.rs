@@ -0,0 +1,212 @@
+use crate::shortcuts::KeyEvent;
+use rdev::{listen, Button, Event, EventType, Key};
+use std::sync::mpsc::Sender;
+
+fn rdev_key_to_vk(key: &Key) -> Option<i32> {
+    match key {
+        Key::MetaLeft => Some(0x5B),
+        Key::MetaRight => Some(0x5C),
+        Key::ControlLeft => Some(0xA2),
+        Key::ControlRight => Some(0xA3),
+        Key::Alt => Some(0xA4),
+        Key::AltGr => Some(0xA5),
+        Key::ShiftLeft => Some(0xA0),
+        Key::ShiftRight => Some(0xA1),
+        Key::KeyA => Some(0x41),
+        Key::KeyB => Some(0x42),
+        Key::KeyC => Some(0x43),
+        Key::KeyD => Some(0x44),
+        Key::KeyE => Some(0x45),
+        Key::KeyF => Some(0x46),
+        Key::KeyG => Some(0x47),
+        Key::KeyH => Some(0x48),
+        Key::KeyI => Some(0x49),
+        Key::KeyJ => Some(0x4A),
+        Key::KeyK => Some(0x4B),
+        Key::KeyL => Some(0x4C),
+        Key::KeyM => Some(0x4D),
+        Key::KeyN => Some(0x4E),
+        Key::KeyO => Some(0x4F),
+        Key::KeyP => Some(0x50),
+        Key::KeyQ => Some(0x51),
+        Key::KeyR => Some(0x52),
+        Key::KeyS => Some(0x53),
+        Key::KeyT => Some(0x54),
+        Key::KeyU => Some(0x55),
+        Key::KeyV => Some(0x56),
+        Key::KeyW => Some(0x57),
+        Key::KeyX => Some(0x58),
+        Key::KeyY => Some(0x59),
+        Key::KeyZ => Some(0x5A),
+        Key::Num0 => Some(0x30),
+        Key::Num1 => Some(0x31),
+        Key::Num2 => Some(0x32),
+        Key::Num3 => Some(0x33),
+        Key::Num4 => Some(0x34),
+        Key::Num5 => Some(0x35),
+        Key::Num6 => Some(0x36),
+        Key::Num7 => Some(0x37),
+        Key::Num8 => Some(0x38),
+        Key::Num9 => Some(0x39),
+        Key::F1 => Some(0x70),
+        Key::F2 => Some(0x71),
+        Key::F3 => Some(0x72),
+        Key::F4 => Some(0x73),
+        Key::F5 => Some(0x74),
+        Key::F6 => Some(0x75),
+        Key::F7 => Some(0x76),
+        Key::F8 => Some(0x77),
+        Key::F9 => Some(0x78),
+        Key::F10 => Some(0x79),
+        Key::F11 => Some(0x7A),
+        Key::F12 => Some(0x7B),
+        Key::Space => Some(0x20),
+        Key::Return => Some(0x0D),
+        Key::Escape => Some(0x1B),
+        Key::Tab => Some(0x09),
+        Key::Backspace => Some(0x08),
+        Key::Delete => Some(0x2E),
+        Key::Insert => Some(0x2D),
+        Key::Home => Some(0x24),
+        Key::End => Some(0x23),
+        Key::PageUp => Some(0x21),
+        Key::PageDown => Some(0x22),
+        Key::UpArrow => Some(0x26),
+        Key::DownArrow => Some(0x28),
+        Key::LeftArrow => Some(0x25),
+        Key::RightArrow => Some(0x27),
+        _ => None,
+    }
+}
+
+/// X buttons 8 and 9 are the back and forward side buttons.
+fn rdev_button_to_vk(button: &Button) -> Option<i32> {
+    match button {
+        Button::Middle => Some(0x04),
+        Button::Unknown(8) => Some(0x05),
+        Button::Unknown(9) => Some(0x06),
+        _ => None,
+    }
+}
+
+/// Inverse of `rdev_key_to_vk`, left-hand variants are used for generic modifiers.
+fn vk_to_rdev_key(vk: i32) -> Option<Key> {
+    match vk {
+        0x5B => Some(Key::MetaLeft),
+        0x5C => Some(Key::MetaRight),
+        0x11 | 0xA2 => Some(Key::ControlLeft),
+        0xA3 => Some(Key::ControlRight),
+        0x12 | 0xA4 => Some(Key::Alt),
+        0xA5 => Some(Key::AltGr),
+        0x10 | 0xA0 => Some(Key::ShiftLeft),
+        0xA1 => Some(Key::ShiftRight),
+        0x41 => Some(Key::KeyA),
+        0x42 => Some(Key::KeyB),
+        0x43 => Some(Key::KeyC),
+        0x44 => Some(Key::KeyD),
+        0x45 => Some(Key::KeyE),
+        0x46 => Some(Key::KeyF),
+        0x47 => Some(Key::KeyG),
+        0x48 => Some(Key::KeyH),
+        0x49 => Some(Key::KeyI),
+        0x4A => Some(Key::KeyJ),
+        0x4B => Some(Key::KeyK),
+        0x4C => Some(Key::KeyL),
+        0x4D => Some(Key::KeyM),
+        0x4E => Some(Key::KeyN),
+        0x4F => Some(Key::KeyO),
+        0x50 => Some(Key::KeyP),
+        0x51 => Some(Key::KeyQ),
+        0x52 => Some(Key::KeyR),
+        0x53 => Some(Key::KeyS),
+        0x54 => Some(Key::KeyT),
+        0x55 => Some(Key::KeyU),
+        0x56 => Some(Key::KeyV),
+        0x57 => Some(Key::KeyW),
+        0x58 => Some(Key::KeyX),
+        0x59 => Some(Key::KeyY),
+        0x5A => Some(Key::KeyZ),
+        0x30 => Some(Key::Num0),
+        0x31 => Some(Key::Num1),
+        0x32 => Some(Key::Num2),
+        0x33 => Some(Key::Num3),
+        0x34 => Some(Key::Num4),
+        0x35 => Some(Key::Num5),
+        0x36 => Some(Key::Num6),
+        0x37 => Some(Key::Num7),
+        0x38 => Some(Key::Num8),
+        0x39 => Some(Key::Num9),
+        0x70 => Some(Key::F1),
+        0x71 => Some(Key::F2),
+        0x72 => Some(Key::F3),
+        0x73 => Some(Key::F4),
+        0x74 => Some(Key::F5),
+        0x75 => Some(Key::F6),
+        0x76 => Some(Key::F7),
+        0x77 => Some(Key::F8),
+        0x78 => Some(Key::F9),
+        0x79 => Some(Key::F10),
+        0x7A => Some(Key::F11),
+        0x7B => Some(Key::F12),
+        0x20 => Some(Key::Space),
+        0x0D => Some(Key::Return),
+        0x1B => Some(Key::Escape),
+        0x09 => Some(Key::Tab),
+        0x08 => Some(Key::Backspace),
+        0x2E => Some(Key::Delete),
+        0x2D => Some(Key::Insert),
+        0x24 => Some(Key::Home),
+        0x23 => Some(Key::End),
+        0x21 => Some(Key::PageUp),
+        0x22 => Some(Key::PageDown),
+        0x26 => Some(Key::UpArrow),
+        0x28 => Some(Key::DownArrow),
+        0x25 => Some(Key::LeftArrow),
+        0x27 => Some(Key::RightArrow),
+        _ => None,
+    }
+}
+
+/// Forward key and mouse button presses and releases from the X server to the
+/// shortcut engine. On Wayland, only keys typed into XWayland windows are seen.
+pub fn listen_keys(sender: Sender<KeyEvent>) {
+    std::thread::spawn(move || {
+        if let Err(error) = listen(move |event: Event| {
+            let key_event = match event.event_type {
+                EventType::KeyPress(key) => rdev_key_to_vk(&key).map(KeyEvent::Down),
+                EventType::KeyRelease(key) => rdev_key_to_vk(&key).map(KeyEvent::Up),
+                EventType::ButtonPress(button) => rdev_button_to_vk(&button).map(KeyEvent::Down),
+                EventType::ButtonRelease(button) => rdev_button_to_vk(&button).map(KeyEvent::Up),
+                _ => None,
+            };
+            if let Some(key_event) = key_event {
+                let _ = sender.send(key_event);
+            }
+        }) {
+            log::error!("Error starting keyboard listener: {:?}", error);
+        }
+    });
+}
+
+/// Press the keys in order and release them in reverse order through XTest.
+pub fn send_keys(keys: &[i32]) -> Result<(), String> {
+    use rdev::simulate;
+    let sleep = |ms| std::thread::sleep(std::time::Duration::from_millis(ms));
+
+    let keys: Vec<Key> = keys.iter().filter_map(|&vk| vk_to_rdev_key(vk)).collect();
+    if keys.is_empty() {
+        return Err("Invalid paste shortcut".to_string());
+    }
+
+    for key in &keys {
+        simulate(&EventType::KeyPress(*key))
+            .map_err(|e| format!("Failed to press {:?}: {:?}", key, e))?;
+        sleep(10);
+    }
+    for key in keys.iter().rev() {
+        simulate(&EventType::KeyRelease(*key))
+            .map_err(|e| format!("Failed to release {:?}: {:?}", key, e))?;
+        sleep(10);
+    }
+    Ok(())
+}
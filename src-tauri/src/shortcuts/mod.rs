@@ -1,7 +1,12 @@
+use crate::audio::{cancel_recording, record_audio, stop_recording};
+use std::collections::{HashMap, HashSet};
 use std::sync::{
-    atomic::{AtomicBool, Ordering},
+    atomic::{AtomicBool, AtomicU64, Ordering},
+    mpsc::{self, Receiver, RecvTimeoutError},
     Arc, Mutex,
 };
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, Manager};
 
 pub struct RecordShortcutKeys(pub Arc<Mutex<Vec<i32>>>);
 
@@ -31,12 +36,26 @@ impl LastTranscriptShortcutKeys {
     }
 }
 
+/// Pseudo key marking a double-tap binding such as "double+right_ctrl".
+/// Counted as held while the key is down for the second time in a row.
+pub const DOUBLE_TAP: i32 = 0x1_0000;
+
 fn key_name_to_vk(name: &str) -> Option<i32> {
     match name.trim().to_lowercase().as_str() {
         "win" | "meta" | "super" => Some(0x5B),
         "ctrl" | "control" => Some(0x11),
         "alt" | "menu" => Some(0x12),
         "shift" => Some(0x10),
+        "left_ctrl" | "lctrl" => Some(0xA2),
+        "right_ctrl" | "rctrl" => Some(0xA3),
+        "left_alt" | "lalt" => Some(0xA4),
+        "right_alt" | "ralt" | "altgr" => Some(0xA5),
+        "left_shift" | "lshift" => Some(0xA0),
+        "right_shift" | "rshift" => Some(0xA1),
+        "mouse_middle" | "mouse3" => Some(0x04),
+        "mouse4" | "mouse_back" => Some(0x05),
+        "mouse5" | "mouse_forward" => Some(0x06),
+        "double" => Some(DOUBLE_TAP),
         "a" => Some(0x41),
         "b" => Some(0x42),
         "c" => Some(0x43),
@@ -110,6 +129,16 @@ fn vk_to_key_name(vk: i32) -> String {
         0x11 => "ctrl".to_string(),
         0x12 => "alt".to_string(),
         0x10 => "shift".to_string(),
+        0xA2 => "left_ctrl".to_string(),
+        0xA3 => "right_ctrl".to_string(),
+        0xA4 => "left_alt".to_string(),
+        0xA5 => "right_alt".to_string(),
+        0xA0 => "left_shift".to_string(),
+        0xA1 => "right_shift".to_string(),
+        0x04 => "mouse_middle".to_string(),
+        0x05 => "mouse4".to_string(),
+        0x06 => "mouse5".to_string(),
+        DOUBLE_TAP => "double".to_string(),
         0x41..=0x5A => {
             let offset = (vk - 0x41) as u8;
             ((b'a' + offset) as char).to_string()
@@ -150,6 +179,22 @@ pub fn parse_binding_keys(binding: &str) -> Vec<i32> {
     keys
 }
 
+/// The generic code of a left/right modifier, matched by plain "ctrl", "alt"...
+fn generic_modifier(vk: i32) -> Option<i32> {
+    match vk {
+        0xA0 | 0xA1 => Some(0x10),
+        0xA2 | 0xA3 => Some(0x11),
+        0xA4 | 0xA5 => Some(0x12),
+        0x5C => Some(0x5B),
+        _ => None,
+    }
+}
+
+/// Whether the key can be sent as a keystroke, unlike mouse buttons and gestures.
+pub fn is_keyboard_key(vk: i32) -> bool {
+    !matches!(vk, 0x04..=0x06 | DOUBLE_TAP)
+}
+
 pub fn keys_to_string(keys: &[i32]) -> String {
     keys.iter()
         .map(|vk| vk_to_key_name(*vk))
@@ -157,15 +202,77 @@ pub fn keys_to_string(keys: &[i32]) -> String {
         .join("+")
 }
 
+/// Whether two keys are pressed by the same key, plain "ctrl" standing for either side.
+fn same_key(a: i32, b: i32) -> bool {
+    a == b || generic_modifier(a) == Some(b) || generic_modifier(b) == Some(a)
+}
+
+/// Whether `keys` holds every key of `chord`, the double-tap marker aside.
+fn contains_chord(keys: &[i32], chord: &[i32]) -> bool {
+    chord
+        .iter()
+        .filter(|&&k| k != DOUBLE_TAP)
+        .all(|&k| keys.iter().any(|&other| same_key(other, k)))
+}
+
+/// Whether pressing one binding also fires the other: the same keys, a double-tap and a
+/// plain press of the same key, or in superset mode a chord contained in the other.
+pub fn bindings_overlap(a: &[i32], b: &[i32], mode: MatchMode) -> bool {
+    let (a_in_b, b_in_a) = (contains_chord(b, a), contains_chord(a, b));
+    match mode {
+        MatchMode::Exact => a_in_b && b_in_a,
+        MatchMode::Superset => a_in_b || b_in_a,
+    }
+}
+
+/// Keys of a binding, rejecting unknown key names and chords that overlap a taken one.
+pub fn validate_binding(
+    binding: &str,
+    taken: &[Vec<i32>],
+    mode: MatchMode,
+) -> Result<Vec<i32>, String> {
+    let unknown: Vec<&str> = binding
+        .split('+')
+        .map(str::trim)
+        .filter(|token| key_name_to_vk(token).is_none())
+        .collect();
+    if !unknown.is_empty() {
+        return Err(format!("Unknown key: {}", unknown.join(", ")));
+    }
+
+    let keys = parse_binding_keys(binding);
+    if keys.is_empty() || keys == [DOUBLE_TAP] {
+        return Err("Invalid shortcut".to_string());
+    }
+    if keys.contains(&DOUBLE_TAP) && keys.len() != 2 {
+        return Err("A double-tap shortcut takes a single key, e.g. double+right_ctrl".to_string());
+    }
+    if let Some(other) = taken
+        .iter()
+        .find(|other| bindings_overlap(&keys, other, mode))
+    {
+        return Err(format!(
+            "Shortcut {} conflicts with {}",
+            keys_to_string(&keys),
+            keys_to_string(other)
+        ));
+    }
+    Ok(keys)
+}
+
+mod actions;
 #[cfg(target_os = "linux")]
 mod linux;
 #[cfg(target_os = "windows")]
 mod windows;
 
+pub use actions::{run_action, ShortcutAction, ShortcutBinding};
 #[cfg(target_os = "linux")]
-pub use linux::init_shortcuts;
+use linux::listen_keys;
+#[cfg(target_os = "linux")]
+pub use linux::{active_backend, is_wayland_session, send_keys};
 #[cfg(target_os = "windows")]
-pub use windows::init_shortcuts;
+use windows::listen_keys;
 
 pub struct TranscriptionSuspended(pub Arc<AtomicBool>);
 
@@ -180,3 +287,726 @@ impl TranscriptionSuspended {
         self.0.store(value, Ordering::SeqCst)
     }
 }
+
+/// User-defined shortcuts, in addition to the record and last transcript ones.
+pub struct CustomShortcuts(pub Arc<Mutex<Vec<(ShortcutAction, Vec<i32>)>>>);
+
+impl CustomShortcuts {
+    pub fn new(bindings: &[ShortcutBinding]) -> Self {
+        Self(Arc::new(Mutex::new(parse_custom_shortcuts(bindings))))
+    }
+    pub fn get(&self) -> Vec<(ShortcutAction, Vec<i32>)> {
+        self.0.lock().unwrap().clone()
+    }
+    pub fn set(&self, bindings: &[ShortcutBinding]) {
+        *self.0.lock().unwrap() = parse_custom_shortcuts(bindings);
+    }
+}
+
+fn parse_custom_shortcuts(bindings: &[ShortcutBinding]) -> Vec<(ShortcutAction, Vec<i32>)> {
+    bindings
+        .iter()
+        .filter_map(|b| {
+            let action = ShortcutAction::parse(&b.action, b.argument.as_deref())?;
+            let keys = parse_binding_keys(&b.binding);
+            (!keys.is_empty()).then_some((action, keys))
+        })
+        .collect()
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RecordMode {
+    /// Record while the shortcut is held
+    PushToTalk,
+    /// First press starts recording, second press stops it
+    Toggle,
+    /// A short tap toggles, a long hold is push-to-talk
+    Hybrid,
+}
+
+impl RecordMode {
+    pub fn parse(mode: &str) -> Option<Self> {
+        match mode {
+            "push_to_talk" => Some(Self::PushToTalk),
+            "toggle" => Some(Self::Toggle),
+            "hybrid" => Some(Self::Hybrid),
+            _ => None,
+        }
+    }
+}
+
+pub struct RecordShortcutMode(pub Arc<Mutex<RecordMode>>);
+
+impl RecordShortcutMode {
+    pub fn new(mode: RecordMode) -> Self {
+        Self(Arc::new(Mutex::new(mode)))
+    }
+    pub fn get(&self) -> RecordMode {
+        *self.0.lock().unwrap()
+    }
+    pub fn set(&self, mode: RecordMode) {
+        *self.0.lock().unwrap() = mode;
+    }
+}
+
+/// Safety cutoff for a recording, in seconds (0 for no limit).
+pub struct MaxRecordingDuration(pub Arc<AtomicU64>);
+
+impl MaxRecordingDuration {
+    pub fn new(seconds: u64) -> Self {
+        Self(Arc::new(AtomicU64::new(seconds)))
+    }
+    pub fn get(&self) -> Option<Duration> {
+        match self.0.load(Ordering::SeqCst) {
+            0 => None,
+            seconds => Some(Duration::from_secs(seconds)),
+        }
+    }
+    pub fn set(&self, seconds: u64) {
+        self.0.store(seconds, Ordering::SeqCst)
+    }
+}
+
+/// In hybrid mode, a press shorter than this keeps recording after release.
+const HYBRID_TAP_THRESHOLD: Duration = Duration::from_millis(300);
+
+/// Starts and stops the recording from presses of the record shortcut,
+/// according to the record mode.
+#[derive(Default)]
+pub struct RecordShortcutHandler {
+    recording_since: Option<Instant>,
+    /// Recording continues after the keys are released
+    latched: bool,
+}
+
+impl RecordShortcutHandler {
+    pub fn on_press(&mut self, app: &AppHandle, keys: &[i32]) {
+        if self.recording_since.is_none() {
+            self.start(app, keys);
+            self.latched = app.state::<RecordShortcutMode>().get() == RecordMode::Toggle;
+        } else if self.latched {
+            self.stop(app, keys);
+        }
+    }
+
+    pub fn on_release(&mut self, app: &AppHandle, keys: &[i32]) {
+        let Some(since) = self.recording_since.filter(|_| !self.latched) else {
+            return;
+        };
+        let mode = app.state::<RecordShortcutMode>().get();
+        if mode == RecordMode::Hybrid && since.elapsed() < HYBRID_TAP_THRESHOLD {
+            self.latched = true;
+            let _ = app.emit("shortcut:latched", keys_to_string(keys));
+        } else {
+            self.stop(app, keys);
+        }
+    }
+
+    /// Stop a recording that reached the maximum duration.
+    pub fn on_tick(&mut self, app: &AppHandle, keys: &[i32]) {
+        let Some(max) = app.state::<MaxRecordingDuration>().get() else {
+            return;
+        };
+        if self
+            .recording_since
+            .is_some_and(|since| since.elapsed() >= max)
+        {
+            log::info!("Maximum recording duration of {}s reached", max.as_secs());
+            let _ = app.emit("recording:max-duration", max.as_secs());
+            self.stop(app, keys);
+        }
+    }
+
+    pub fn next_deadline(&self, app: &AppHandle) -> Option<Instant> {
+        let max = app.state::<MaxRecordingDuration>().get()?;
+        Some(self.recording_since? + max)
+    }
+
+    /// Discard the current recording, if any, without transcribing it.
+    pub fn on_cancel(&mut self, app: &AppHandle, keys: &[i32]) {
+        if self.recording_since.take().is_none() {
+            return;
+        }
+        self.latched = false;
+        cancel_recording(app);
+        let _ = app.emit("shortcut:cancel", keys_to_string(keys));
+    }
+
+    fn start(&mut self, app: &AppHandle, keys: &[i32]) {
+        record_audio(app);
+        self.recording_since = Some(Instant::now());
+        let _ = app.emit("shortcut:start", keys_to_string(keys));
+    }
+
+    fn stop(&mut self, app: &AppHandle, keys: &[i32]) {
+        let _ = stop_recording(app);
+        self.recording_since = None;
+        self.latched = false;
+        let _ = app.emit("shortcut:stop", keys_to_string(keys));
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum KeyEvent {
+    Down(i32),
+    Up(i32),
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ShortcutTransition {
+    Pressed(ShortcutAction),
+    Released(ShortcutAction),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MatchMode {
+    /// Exactly the shortcut keys are down: ctrl+shift+space does not fire ctrl+space
+    Exact,
+    /// At least the shortcut keys are down, extra keys are ignored
+    Superset,
+}
+
+impl MatchMode {
+    pub fn parse(mode: &str) -> Option<Self> {
+        match mode {
+            "exact" => Some(Self::Exact),
+            "superset" => Some(Self::Superset),
+            _ => None,
+        }
+    }
+}
+
+pub struct ShortcutMatchMode(pub Arc<Mutex<MatchMode>>);
+
+impl ShortcutMatchMode {
+    pub fn new(mode: MatchMode) -> Self {
+        Self(Arc::new(Mutex::new(mode)))
+    }
+    pub fn get(&self) -> MatchMode {
+        *self.0.lock().unwrap()
+    }
+    pub fn set(&self, mode: MatchMode) {
+        *self.0.lock().unwrap() = mode;
+    }
+}
+
+/// A release followed by a press of the same key within this delay is a bounce.
+const DEBOUNCE: Duration = Duration::from_millis(25);
+/// A press shorter than this counts as the first tap of a double-tap.
+const DOUBLE_TAP_PRESS: Duration = Duration::from_millis(250);
+/// Longest pause between the two taps.
+const DOUBLE_TAP_GAP: Duration = Duration::from_millis(400);
+
+/// Whether a binding key is held, a generic modifier being held by either side.
+fn is_held(pressed: &HashSet<i32>, key: i32) -> bool {
+    pressed
+        .iter()
+        .any(|&vk| vk == key || generic_modifier(vk) == Some(key))
+}
+
+/// Whether a pressed key is part of the binding.
+fn is_bound(keys: &[i32], vk: i32) -> bool {
+    keys.contains(&vk) || generic_modifier(vk).is_some_and(|g| keys.contains(&g))
+}
+
+/// Turns key events into shortcut presses and releases.
+/// Time is passed in by the caller so the matcher can be driven by synthetic events.
+#[derive(Default)]
+pub struct ShortcutMatcher {
+    pressed: HashSet<i32>,
+    /// Released keys still counted as down until their debounce deadline
+    pending_releases: HashMap<i32, Instant>,
+    active: Vec<ShortcutAction>,
+    /// Last key pressed and when, to tell taps from holds
+    last_press: Option<(i32, Instant)>,
+    /// Key released after a short tap, and when
+    last_tap: Option<(i32, Instant)>,
+    /// Key whose second press holds `DOUBLE_TAP`
+    double_tapped: Option<i32>,
+}
+
+impl ShortcutMatcher {
+    pub fn handle(
+        &mut self,
+        event: KeyEvent,
+        now: Instant,
+        bindings: &[(ShortcutAction, Vec<i32>)],
+        mode: MatchMode,
+    ) -> Vec<ShortcutTransition> {
+        let mut transitions = self.flush(now, bindings);
+        match event {
+            KeyEvent::Down(vk) => {
+                // Bounce, or auto-repeat of a held key
+                if self.pending_releases.remove(&vk).is_some() || !self.pressed.insert(vk) {
+                    return transitions;
+                }
+                let tap = self.last_tap.take();
+                // A double-tap binding takes precedence over the key's own bindings
+                let double_tap = tap
+                    .is_some_and(|(key, at)| key == vk && now - at <= DOUBLE_TAP_GAP)
+                    && bindings
+                        .iter()
+                        .any(|(_, keys)| keys.contains(&DOUBLE_TAP) && is_bound(keys, vk));
+                if double_tap {
+                    self.pressed.insert(DOUBLE_TAP);
+                    self.double_tapped = Some(vk);
+                }
+                self.last_press = Some((vk, now));
+
+                for (action, keys) in bindings {
+                    if double_tap && !keys.contains(&DOUBLE_TAP) {
+                        continue;
+                    }
+                    if is_bound(keys, vk)
+                        && !self.active.contains(action)
+                        && self.matches(keys, mode, bindings)
+                    {
+                        self.active.push(action.clone());
+                        transitions.push(ShortcutTransition::Pressed(action.clone()));
+                    }
+                }
+            }
+            KeyEvent::Up(vk) => {
+                if self.pressed.contains(&vk) {
+                    self.pending_releases.insert(vk, now + DEBOUNCE);
+                }
+                let tapped = self
+                    .last_press
+                    .is_some_and(|(key, at)| key == vk && now - at < DOUBLE_TAP_PRESS);
+                // The second tap of a double-tap does not start another one
+                if tapped && self.double_tapped != Some(vk) {
+                    self.last_tap = Some((vk, now));
+                }
+            }
+        }
+        transitions
+    }
+
+    /// Apply the releases whose debounce delay has elapsed.
+    pub fn flush(
+        &mut self,
+        now: Instant,
+        bindings: &[(ShortcutAction, Vec<i32>)],
+    ) -> Vec<ShortcutTransition> {
+        let mut transitions = Vec::new();
+        let released: Vec<i32> = self
+            .pending_releases
+            .iter()
+            .filter(|(_, &deadline)| deadline <= now)
+            .map(|(&vk, _)| vk)
+            .collect();
+        for vk in released {
+            self.pending_releases.remove(&vk);
+            self.pressed.remove(&vk);
+            if self.double_tapped == Some(vk) {
+                self.double_tapped = None;
+                self.pressed.remove(&DOUBLE_TAP);
+            }
+        }
+
+        let pressed = &self.pressed;
+        self.active.retain(|action| {
+            // Several chords may be bound to the same action
+            let held = bindings
+                .iter()
+                .filter(|(a, _)| a == action)
+                .any(|(_, keys)| keys.iter().all(|&k| is_held(pressed, k)));
+            if !held {
+                transitions.push(ShortcutTransition::Released(action.clone()));
+            }
+            held
+        });
+        transitions
+    }
+
+    pub fn next_deadline(&self) -> Option<Instant> {
+        self.pending_releases.values().min().copied()
+    }
+
+    fn matches(
+        &self,
+        keys: &[i32],
+        mode: MatchMode,
+        bindings: &[(ShortcutAction, Vec<i32>)],
+    ) -> bool {
+        if keys.is_empty() || !keys.iter().all(|&k| is_held(&self.pressed, k)) {
+            return false;
+        }
+        match mode {
+            // Keys held for an active shortcut do not count as extra,
+            // so escape can cancel while the record keys are held
+            MatchMode::Exact => self.pressed.iter().all(|&k| {
+                is_bound(keys, k)
+                    || bindings
+                        .iter()
+                        .any(|(a, held)| self.active.contains(a) && is_bound(held, k))
+            }),
+            MatchMode::Superset => true,
+        }
+    }
+}
+
+fn current_bindings(app: &AppHandle) -> Vec<(ShortcutAction, Vec<i32>)> {
+    let mut bindings = vec![
+        (
+            ShortcutAction::Record,
+            app.state::<RecordShortcutKeys>().get(),
+        ),
+        (
+            ShortcutAction::PasteLastTranscript,
+            app.state::<LastTranscriptShortcutKeys>().get(),
+        ),
+    ];
+    bindings.extend(app.state::<CustomShortcuts>().get());
+    bindings
+}
+
+/// Start the platform key listener and dispatch shortcut actions from its events.
+pub fn init_shortcuts(app: AppHandle) {
+    let (sender, receiver) = mpsc::channel();
+    listen_keys(&app, sender);
+    std::thread::spawn(move || run_shortcuts(app, receiver));
+}
+
+fn run_shortcuts(app: AppHandle, events: Receiver<KeyEvent>) {
+    let mut matcher = ShortcutMatcher::default();
+    let mut record_handler = RecordShortcutHandler::default();
+
+    loop {
+        let deadline = [matcher.next_deadline(), record_handler.next_deadline(&app)]
+            .into_iter()
+            .flatten()
+            .min();
+        let event = match deadline {
+            Some(deadline) => {
+                match events.recv_timeout(deadline.saturating_duration_since(Instant::now())) {
+                    Ok(event) => Some(event),
+                    Err(RecvTimeoutError::Timeout) => None,
+                    Err(RecvTimeoutError::Disconnected) => return,
+                }
+            }
+            None => match events.recv() {
+                Ok(event) => Some(event),
+                Err(_) => return,
+            },
+        };
+
+        let bindings = current_bindings(&app);
+        let now = Instant::now();
+        let transitions = match event {
+            Some(event) => {
+                let mode = app.state::<ShortcutMatchMode>().get();
+                matcher.handle(event, now, &bindings, mode)
+            }
+            None => matcher.flush(now, &bindings),
+        };
+
+        let record_keys = &bindings[0].1;
+        let suspended = app.state::<TranscriptionSuspended>().get();
+        for transition in transitions {
+            match transition {
+                // Releases still go through so a recording never gets stuck
+                ShortcutTransition::Pressed(action)
+                    if suspended && !action.runs_when_suspended() => {}
+                ShortcutTransition::Pressed(ShortcutAction::Record) => {
+                    record_handler.on_press(&app, record_keys)
+                }
+                ShortcutTransition::Released(ShortcutAction::Record) => {
+                    record_handler.on_release(&app, record_keys)
+                }
+                ShortcutTransition::Pressed(ShortcutAction::CancelRecording) => {
+                    record_handler.on_cancel(&app, record_keys)
+                }
+                ShortcutTransition::Pressed(action) => run_action(&app, &action),
+                ShortcutTransition::Released(_) => {}
+            }
+        }
+        record_handler.on_tick(&app, record_keys);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use KeyEvent::{Down, Up};
+    use ShortcutAction::{CancelRecording, PasteLastTranscript, Record};
+    use ShortcutTransition::{Pressed, Released};
+
+    const LEFT_CTRL: i32 = 0xA2;
+    const RIGHT_CTRL: i32 = 0xA3;
+    const LEFT_SHIFT: i32 = 0xA0;
+    const SPACE: i32 = 0x20;
+    const ESCAPE: i32 = 0x1B;
+
+    fn bindings(list: &[(ShortcutAction, &str)]) -> Vec<(ShortcutAction, Vec<i32>)> {
+        list.iter()
+            .map(|(action, binding)| (action.clone(), parse_binding_keys(binding)))
+            .collect()
+    }
+
+    /// Feed timed events, in milliseconds from the start, and collect the transitions.
+    fn run(
+        events: &[(u64, KeyEvent)],
+        bindings: &[(ShortcutAction, Vec<i32>)],
+        mode: MatchMode,
+    ) -> Vec<ShortcutTransition> {
+        let start = Instant::now();
+        let mut matcher = ShortcutMatcher::default();
+        let mut transitions = Vec::new();
+        for &(ms, event) in events {
+            let now = start + Duration::from_millis(ms);
+            transitions.extend(matcher.handle(event, now, bindings, mode));
+        }
+        // Let the last releases through
+        let end = events.last().map_or(0, |&(ms, _)| ms) + 1000;
+        transitions.extend(matcher.flush(start + Duration::from_millis(end), bindings));
+        transitions
+    }
+
+    #[test]
+    fn exact_mode_ignores_a_shortcut_with_extra_keys() {
+        let bindings = bindings(&[
+            (Record, "ctrl+space"),
+            (PasteLastTranscript, "ctrl+shift+space"),
+        ]);
+        let events = [
+            (0, Down(LEFT_CTRL)),
+            (10, Down(LEFT_SHIFT)),
+            (20, Down(SPACE)),
+            (100, Up(SPACE)),
+        ];
+        assert_eq!(
+            run(&events, &bindings, MatchMode::Exact),
+            vec![Pressed(PasteLastTranscript), Released(PasteLastTranscript)]
+        );
+    }
+
+    #[test]
+    fn superset_mode_fires_every_contained_shortcut() {
+        let bindings = bindings(&[
+            (Record, "ctrl+space"),
+            (PasteLastTranscript, "ctrl+shift+space"),
+        ]);
+        let events = [
+            (0, Down(LEFT_CTRL)),
+            (10, Down(LEFT_SHIFT)),
+            (20, Down(SPACE)),
+            (100, Up(SPACE)),
+        ];
+        let transitions = run(&events, &bindings, MatchMode::Superset);
+        assert_eq!(
+            transitions[..2],
+            [Pressed(Record), Pressed(PasteLastTranscript)]
+        );
+        assert!(transitions.contains(&Released(Record)));
+        assert!(transitions.contains(&Released(PasteLastTranscript)));
+    }
+
+    #[test]
+    fn either_side_matches_a_generic_modifier() {
+        let bindings = bindings(&[(Record, "ctrl+space")]);
+        let events = [(0, Down(RIGHT_CTRL)), (10, Down(SPACE)), (100, Up(SPACE))];
+        assert_eq!(
+            run(&events, &bindings, MatchMode::Exact),
+            vec![Pressed(Record), Released(Record)]
+        );
+    }
+
+    #[test]
+    fn side_specific_binding_ignores_the_other_side() {
+        let bindings = bindings(&[(Record, "right_ctrl+space")]);
+        let events = [(0, Down(LEFT_CTRL)), (10, Down(SPACE))];
+        assert_eq!(run(&events, &bindings, MatchMode::Exact), vec![]);
+    }
+
+    #[test]
+    fn bounce_does_not_release_the_shortcut() {
+        let bindings = bindings(&[(Record, "ctrl+space")]);
+        let events = [
+            (0, Down(LEFT_CTRL)),
+            (10, Down(SPACE)),
+            // Contact bounce, shorter than DEBOUNCE
+            (500, Up(SPACE)),
+            (510, Down(SPACE)),
+            (1000, Up(SPACE)),
+        ];
+        assert_eq!(
+            run(&events, &bindings, MatchMode::Exact),
+            vec![Pressed(Record), Released(Record)]
+        );
+    }
+
+    #[test]
+    fn auto_repeat_does_not_press_again() {
+        let bindings = bindings(&[(Record, "ctrl+space")]);
+        let events = [
+            (0, Down(LEFT_CTRL)),
+            (10, Down(SPACE)),
+            (500, Down(SPACE)),
+            (530, Down(SPACE)),
+        ];
+        assert_eq!(
+            run(&events, &bindings, MatchMode::Exact),
+            vec![Pressed(Record)]
+        );
+    }
+
+    #[test]
+    fn release_is_reported_once_whichever_key_goes_first() {
+        let bindings = bindings(&[(Record, "ctrl+space")]);
+        let start = Instant::now();
+        let at = |ms| start + Duration::from_millis(ms);
+        let mut matcher = ShortcutMatcher::default();
+        let mode = MatchMode::Exact;
+
+        matcher.handle(Down(LEFT_CTRL), at(0), &bindings, mode);
+        assert_eq!(
+            matcher.handle(Down(SPACE), at(10), &bindings, mode),
+            vec![Pressed(Record)]
+        );
+        // Released once the debounce delay of the first released key is over
+        assert_eq!(
+            matcher.handle(Up(LEFT_CTRL), at(200), &bindings, mode),
+            vec![]
+        );
+        assert_eq!(matcher.next_deadline(), Some(at(200) + DEBOUNCE));
+        assert_eq!(matcher.flush(at(210), &bindings), vec![]);
+        assert_eq!(matcher.flush(at(230), &bindings), vec![Released(Record)]);
+        // The other key going up afterwards releases nothing
+        assert_eq!(matcher.handle(Up(SPACE), at(300), &bindings, mode), vec![]);
+        assert_eq!(matcher.flush(at(400), &bindings), vec![]);
+    }
+
+    #[test]
+    fn cancel_fires_while_the_record_keys_are_held() {
+        let bindings = bindings(&[(Record, "ctrl+space"), (CancelRecording, "escape")]);
+        let events = [
+            (0, Down(LEFT_CTRL)),
+            (10, Down(SPACE)),
+            (300, Down(ESCAPE)),
+            (400, Up(ESCAPE)),
+        ];
+        let transitions = run(&events, &bindings, MatchMode::Exact);
+        assert_eq!(
+            transitions[..2],
+            [Pressed(Record), Pressed(CancelRecording)]
+        );
+    }
+
+    #[test]
+    fn double_tap_takes_precedence_over_the_single_key() {
+        let bindings = bindings(&[
+            (Record, "double+right_ctrl"),
+            (PasteLastTranscript, "right_ctrl"),
+        ]);
+        assert_eq!(bindings[0].1, vec![DOUBLE_TAP, RIGHT_CTRL]);
+        let events = [
+            (0, Down(RIGHT_CTRL)),
+            (100, Up(RIGHT_CTRL)),
+            (300, Down(RIGHT_CTRL)),
+            (1000, Up(RIGHT_CTRL)),
+        ];
+        assert_eq!(
+            run(&events, &bindings, MatchMode::Exact),
+            vec![
+                Pressed(PasteLastTranscript),
+                Released(PasteLastTranscript),
+                Pressed(Record),
+                Released(Record)
+            ]
+        );
+    }
+
+    #[test]
+    fn double_tap_needs_two_quick_taps() {
+        let bindings = bindings(&[(Record, "double+right_ctrl")]);
+        // The first press is held too long to be a tap
+        let held = [
+            (0, Down(RIGHT_CTRL)),
+            (400, Up(RIGHT_CTRL)),
+            (500, Down(RIGHT_CTRL)),
+        ];
+        assert_eq!(run(&held, &bindings, MatchMode::Exact), vec![]);
+        // The second press comes too late
+        let slow = [
+            (0, Down(RIGHT_CTRL)),
+            (100, Up(RIGHT_CTRL)),
+            (600, Down(RIGHT_CTRL)),
+        ];
+        assert_eq!(run(&slow, &bindings, MatchMode::Exact), vec![]);
+    }
+
+    #[test]
+    fn third_tap_does_not_start_another_double_tap() {
+        let bindings = bindings(&[(Record, "double+right_ctrl")]);
+        let events = [
+            (0, Down(RIGHT_CTRL)),
+            (100, Up(RIGHT_CTRL)),
+            (200, Down(RIGHT_CTRL)),
+            (300, Up(RIGHT_CTRL)),
+            (400, Down(RIGHT_CTRL)),
+        ];
+        assert_eq!(
+            run(&events, &bindings, MatchMode::Exact),
+            vec![Pressed(Record), Released(Record)]
+        );
+    }
+
+    #[test]
+    fn overlapping_bindings_are_rejected() {
+        let validate = |binding: &str, taken: &str, mode: MatchMode| {
+            validate_binding(binding, &[parse_binding_keys(taken)], mode)
+        };
+        let cases = [
+            ("shift+ctrl+space", "ctrl+shift+space"),
+            ("ctrl+space", "left_ctrl+space"),
+            ("double+right_ctrl", "right_ctrl"),
+            ("right_ctrl", "double+right_ctrl"),
+        ];
+        for (binding, taken) in cases {
+            for mode in [MatchMode::Exact, MatchMode::Superset] {
+                assert!(
+                    validate(binding, taken, mode).is_err(),
+                    "{binding} / {taken}"
+                );
+            }
+        }
+
+        // A chord contained in another only overlaps when extra keys are ignored
+        for (binding, taken) in [
+            ("ctrl+space", "ctrl+shift+space"),
+            ("ctrl+shift+space", "ctrl+space"),
+        ] {
+            assert!(validate(binding, taken, MatchMode::Exact).is_ok());
+            assert!(validate(binding, taken, MatchMode::Superset).is_err());
+        }
+
+        let cases = [
+            ("left_ctrl+space", "right_ctrl+space"),
+            ("double+left_ctrl", "double+right_ctrl"),
+            ("ctrl+space", "alt+space"),
+        ];
+        for (binding, taken) in cases {
+            for mode in [MatchMode::Exact, MatchMode::Superset] {
+                assert!(
+                    validate(binding, taken, mode).is_ok(),
+                    "{binding} / {taken}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn invalid_bindings_are_rejected() {
+        for binding in ["", "double", "ctrl+spce", "double+ctrl+space"] {
+            assert!(
+                validate_binding(binding, &[], MatchMode::Exact).is_err(),
+                "{binding}"
+            );
+        }
+        assert_eq!(
+            validate_binding("Ctrl+Space", &[], MatchMode::Exact),
+            Ok(vec![0x11, SPACE])
+        );
+    }
+}
@@ -1,65 +1,88 @@
-use crate::audio::write_transcription;
-use crate::audio::{record_audio, stop_recording};
-use crate::history::get_last_transcription;
-use crate::shortcuts::{
-    keys_to_string, LastTranscriptShortcutKeys, RecordShortcutKeys, TranscriptionSuspended,
-};
-use std::time::Duration;
-use tauri::{AppHandle, Emitter, Manager};
-
-use windows_sys::Win32::UI::Input::KeyboardAndMouse::GetAsyncKeyState;
-
-fn check_keys_pressed(keys: &[i32]) -> bool {
-    keys.iter()
-        .all(|&vk| (unsafe { GetAsyncKeyState(vk) } as u16 & 0x8000) != 0)
-}
+use crate::shortcuts::KeyEvent;
+use once_cell::sync::OnceCell;
+use std::sync::mpsc::Sender;
+use std::sync::Mutex;
+use tauri::AppHandle;
 
-pub fn init_shortcuts(app: AppHandle) {
-    std::thread::spawn(move || {
-        let app_handle = app.clone();
-        let mut is_recording = false;
-        let mut last_transcript_pressed = false;
+use windows_sys::Win32::Foundation::{LPARAM, LRESULT, WPARAM};
+use windows_sys::Win32::UI::WindowsAndMessaging::{
+    CallNextHookEx, GetMessageW, SetWindowsHookExW, KBDLLHOOKSTRUCT, LLMHF_INJECTED, MSG,
+    MSLLHOOKSTRUCT, WH_KEYBOARD_LL, WH_MOUSE_LL, WM_KEYDOWN, WM_KEYUP, WM_MBUTTONDOWN,
+    WM_MBUTTONUP, WM_SYSKEYDOWN, WM_SYSKEYUP, WM_XBUTTONDOWN, WM_XBUTTONUP, XBUTTON1,
+};
 
-        loop {
-            if app_handle.state::<TranscriptionSuspended>().get() {
-                std::thread::sleep(Duration::from_millis(32));
-                continue;
-            }
+/// Keys synthesized with SendInput, such as our own paste chord
+const LLKHF_INJECTED: u32 = 0x10;
 
-            let record_required_keys = app_handle.state::<RecordShortcutKeys>().get();
-            let last_transcript_required_keys =
-                app_handle.state::<LastTranscriptShortcutKeys>().get();
+static SENDER: OnceCell<Mutex<Sender<KeyEvent>>> = OnceCell::new();
 
-            if record_required_keys.is_empty() {
-                std::thread::sleep(Duration::from_millis(32));
-                continue;
-            }
+unsafe extern "system" fn keyboard_hook(code: i32, wparam: WPARAM, lparam: LPARAM) -> LRESULT {
+    if code >= 0 {
+        let info = &*(lparam as *const KBDLLHOOKSTRUCT);
+        // Modifiers are reported as their left/right variants
+        let vk = info.vkCode as i32;
+        let key_event = match wparam as u32 {
+            _ if info.flags & LLKHF_INJECTED != 0 => None,
+            WM_KEYDOWN | WM_SYSKEYDOWN => Some(KeyEvent::Down(vk)),
+            WM_KEYUP | WM_SYSKEYUP => Some(KeyEvent::Up(vk)),
+            _ => None,
+        };
+        if let Some(key_event) = key_event {
+            send(key_event);
+        }
+    }
+    CallNextHookEx(0, code, wparam, lparam)
+}
 
-            let all_record_keys_down = check_keys_pressed(&record_required_keys);
-            let all_last_transcript_keys_down = check_keys_pressed(&last_transcript_required_keys);
+unsafe extern "system" fn mouse_hook(code: i32, wparam: WPARAM, lparam: LPARAM) -> LRESULT {
+    if code >= 0 {
+        let info = &*(lparam as *const MSLLHOOKSTRUCT);
+        // The high word tells which side button was used
+        let side_button = if (info.mouseData >> 16) as u16 == XBUTTON1 {
+            0x05
+        } else {
+            0x06
+        };
+        let key_event = match wparam as u32 {
+            _ if info.flags & LLMHF_INJECTED != 0 => None,
+            WM_MBUTTONDOWN => Some(KeyEvent::Down(0x04)),
+            WM_MBUTTONUP => Some(KeyEvent::Up(0x04)),
+            WM_XBUTTONDOWN => Some(KeyEvent::Down(side_button)),
+            WM_XBUTTONUP => Some(KeyEvent::Up(side_button)),
+            _ => None,
+        };
+        if let Some(key_event) = key_event {
+            send(key_event);
+        }
+    }
+    CallNextHookEx(0, code, wparam, lparam)
+}
 
-            if !is_recording && all_record_keys_down {
-                record_audio(&app_handle);
-                is_recording = true;
-                let _ = app_handle.emit("shortcut:start", keys_to_string(&record_required_keys));
-            }
-            if is_recording && !all_record_keys_down {
-                let _ = stop_recording(&app_handle);
-                is_recording = false;
-                let _ = app_handle.emit("shortcut:stop", keys_to_string(&record_required_keys));
-            }
+fn send(key_event: KeyEvent) {
+    if let Some(sender) = SENDER.get() {
+        let _ = sender.lock().unwrap().send(key_event);
+    }
+}
 
-            if !last_transcript_pressed && all_last_transcript_keys_down {
-                if let Ok(last_transcript) = get_last_transcription(&app_handle) {
-                    let _ = write_transcription(&app_handle, &last_transcript);
-                }
-                last_transcript_pressed = true;
-            }
-            if last_transcript_pressed && !all_last_transcript_keys_down {
-                last_transcript_pressed = false;
-            }
+/// Forward key and mouse button presses and releases to the shortcut engine
+/// with low-level keyboard and mouse hooks.
+pub fn listen_keys(_app: &AppHandle, sender: Sender<KeyEvent>) {
+    if SENDER.set(Mutex::new(sender)).is_err() {
+        log::warn!("Keyboard listener already running");
+        return;
+    }
 
-            std::thread::sleep(Duration::from_millis(32));
+    std::thread::spawn(|| unsafe {
+        // The hooks are called on this thread, which must pump messages
+        let hook = SetWindowsHookExW(WH_KEYBOARD_LL, Some(keyboard_hook), 0, 0);
+        if hook == 0 {
+            log::error!("Error starting keyboard listener");
+            return;
+        }
+        if SetWindowsHookExW(WH_MOUSE_LL, Some(mouse_hook), 0, 0) == 0 {
+            log::error!("Error starting mouse listener");
         }
+        let mut msg: MSG = std::mem::zeroed();
+        while GetMessageW(&mut msg, 0, 0, 0) > 0 {}
     });
 }
@@ -0,0 +1,148 @@
+use crate::audio;
+use crate::error;
+use crate::overlay::{self, OverlayUpdate};
+use crate::settings;
+use serde::Serialize;
+use std::path::PathBuf;
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    mpsc::{self, Sender},
+    Arc, Mutex,
+};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager};
+
+/// How long the expanded overlay shows the final text before hiding.
+const FINISHED_OVERLAY_DURATION: Duration = Duration::from_millis(1500);
+
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum TranscriptionState {
+    Recording,
+    Transcribing,
+    Done,
+    Error,
+}
+
+/// Payload of the "transcription-state" event.
+#[derive(Serialize, Clone, Debug)]
+pub struct TranscriptionStatus {
+    pub state: TranscriptionState,
+    /// Recordings waiting for or in transcription
+    pub pending: usize,
+    /// Final text, once done
+    pub text: Option<String>,
+    pub error: Option<String>,
+}
+
+/// Recordings waiting to be transcribed, in order, by a single worker thread.
+pub struct TranscriptionQueue {
+    sender: Mutex<Sender<PathBuf>>,
+    pending: Arc<AtomicUsize>,
+}
+
+impl TranscriptionQueue {
+    pub fn pending(&self) -> usize {
+        self.pending.load(Ordering::SeqCst)
+    }
+}
+
+pub fn emit_state(
+    app: &AppHandle,
+    state: TranscriptionState,
+    text: Option<String>,
+    error: Option<String>,
+) {
+    let pending = app
+        .try_state::<TranscriptionQueue>()
+        .map_or(0, |queue| queue.pending());
+    let _ = app.emit(
+        "transcription-state",
+        TranscriptionStatus {
+            state,
+            pending,
+            text,
+            error,
+        },
+    );
+}
+
+/// Start the worker that transcribes and writes out queued recordings,
+/// so the shortcut thread is free to start the next recording meanwhile.
+pub fn init_transcription_worker(app: &AppHandle) {
+    let (sender, receiver) = mpsc::channel::<PathBuf>();
+    let pending = Arc::new(AtomicUsize::new(0));
+    app.manage(TranscriptionQueue {
+        sender: Mutex::new(sender),
+        pending: pending.clone(),
+    });
+
+    let app = app.clone();
+    std::thread::spawn(move || {
+        for path in receiver {
+            let result = audio::transcribe_and_write(&app, &path);
+            pending.fetch_sub(1, Ordering::SeqCst);
+            // An error stays on the overlay for a while, see `error::report`
+            let hide_overlay = result.is_ok();
+            match result {
+                Ok(text) => {
+                    overlay::emit_overlay_update(
+                        &app,
+                        OverlayUpdate::Finished { text: text.clone() },
+                    );
+                    emit_state(&app, TranscriptionState::Done, Some(text), None);
+                }
+                Err(e) => {
+                    log::error!("Transcription of {} failed", path.display());
+                    error::report(&app, &e);
+                    emit_state(&app, TranscriptionState::Error, None, Some(e.to_string()));
+                }
+            }
+            if pending.load(Ordering::SeqCst) == 0 && !audio::is_recording() {
+                on_idle(&app, hide_overlay);
+            }
+        }
+    });
+}
+
+fn on_idle(app: &AppHandle, hide_overlay: bool) {
+    if let Err(e) = audio::cleanup_recordings(app) {
+        log::error!("Failed to cleanup recordings: {}", e);
+    }
+    let s = settings::load_settings(app);
+    if hide_overlay && s.overlay_mode.as_str() == "recording" {
+        if overlay::is_expanded(&s) {
+            hide_overlay_later(app, FINISHED_OVERLAY_DURATION);
+        } else {
+            overlay::hide_recording_overlay(app);
+        }
+    }
+}
+
+/// Hide the overlay after a while, unless a recording or transcription started meanwhile.
+pub fn hide_overlay_later(app: &AppHandle, delay: Duration) {
+    let app = app.clone();
+    std::thread::spawn(move || {
+        std::thread::sleep(delay);
+        let busy = audio::is_recording()
+            || app
+                .try_state::<TranscriptionQueue>()
+                .is_some_and(|queue| queue.pending() > 0);
+        if !busy {
+            overlay::hide_recording_overlay(&app);
+        }
+    });
+}
+
+/// Queue a recording for transcription.
+pub fn enqueue(app: &AppHandle, path: PathBuf) {
+    let queue = app.state::<TranscriptionQueue>();
+    queue.pending.fetch_add(1, Ordering::SeqCst);
+    if queue.sender.lock().unwrap().send(path).is_err() {
+        queue.pending.fetch_sub(1, Ordering::SeqCst);
+        log::error!("Transcription worker is not running");
+        return;
+    }
+    emit_state(app, TranscriptionState::Transcribing, None, None);
+    overlay::emit_overlay_update(app, OverlayUpdate::Transcribing);
+}
@@ -1,48 +1,355 @@
-use tauri::menu::{Menu, MenuItem};
+use crate::audio;
+use crate::history;
+use crate::http_api::{spawn_http_api_thread, HttpApiState};
+use crate::settings;
+use crate::shortcuts::{run_action, ShortcutAction, TranscriptionSuspended};
+use crate::transcription::TranscriptionQueue;
+use tauri::image::Image;
+use tauri::menu::{CheckMenuItem, Menu, MenuItem, PredefinedMenuItem, Submenu};
 use tauri::tray::{TrayIconBuilder, TrayIconEvent};
-use tauri::{AppHandle, Manager};
+use tauri::{AppHandle, Emitter, Listener, Manager};
+use tauri_plugin_clipboard_manager::ClipboardExt;
 
-pub fn setup_tray(app: &AppHandle) -> Result<(), Box<dyn std::error::Error>> {
-    let show_i = MenuItem::with_id(app, "show", "Open Murmure", true, None::<&str>)?;
-    let quit_i = MenuItem::with_id(app, "quit", "Quit", true, None::<&str>)?;
-    let menu = Menu::with_items(app, &[&show_i, &quit_i])?;
+const TRAY_ID: &str = "main";
+const RECENT_TRANSCRIPTIONS: usize = 5;
+const RECENT_LABEL_CHARS: usize = 40;
+const RECORDING_BADGE: [u8; 3] = [220, 38, 38];
+const TRANSCRIBING_BADGE: [u8; 3] = [245, 158, 11];
 
-    let builder = TrayIconBuilder::new()
-        .menu(&menu)
-        .on_menu_event(|app, event| match event.id.as_ref() {
-            "show" => {
-                if let Some(window) = app.get_webview_window("main") {
-                    let _ = window.show();
-                    let _ = window.set_focus();
-                }
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum TrayState {
+    Idle,
+    Recording,
+    Transcribing,
+    Suspended,
+}
+
+impl TrayState {
+    fn current(app: &AppHandle) -> Self {
+        if audio::is_recording() {
+            Self::Recording
+        } else if app
+            .try_state::<TranscriptionQueue>()
+            .is_some_and(|queue| queue.pending() > 0)
+        {
+            Self::Transcribing
+        } else if app
+            .try_state::<TranscriptionSuspended>()
+            .is_some_and(|suspended| suspended.get())
+        {
+            Self::Suspended
+        } else {
+            Self::Idle
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            Self::Idle => "Ready",
+            Self::Recording => "Recording",
+            Self::Transcribing => "Transcribing",
+            Self::Suspended => "Suspended",
+        }
+    }
+}
+
+/// The app icon, greyed out when suspended or with a colored dot while busy.
+fn state_icon(app: &AppHandle, state: TrayState) -> Option<Image<'static>> {
+    let base = app.default_window_icon()?;
+    let (width, height) = (base.width(), base.height());
+    let mut rgba = base.rgba().to_vec();
+    match state {
+        TrayState::Idle => {}
+        TrayState::Suspended => {
+            for pixel in rgba.chunks_exact_mut(4) {
+                let gray = (0.299 * pixel[0] as f32
+                    + 0.587 * pixel[1] as f32
+                    + 0.114 * pixel[2] as f32) as u8;
+                pixel[..3].fill(gray);
+                pixel[3] /= 2;
+            }
+        }
+        TrayState::Recording => draw_badge(&mut rgba, width, height, RECORDING_BADGE),
+        TrayState::Transcribing => draw_badge(&mut rgba, width, height, TRANSCRIBING_BADGE),
+    }
+    Some(Image::new_owned(rgba, width, height))
+}
+
+/// Dot in the bottom right corner, a quarter of the icon wide.
+fn draw_badge(rgba: &mut [u8], width: u32, height: u32, color: [u8; 3]) {
+    let radius = width.min(height) as f32 / 4.0;
+    let (cx, cy) = (width as f32 - radius, height as f32 - radius);
+    for y in 0..height {
+        for x in 0..width {
+            let (dx, dy) = (x as f32 + 0.5 - cx, y as f32 + 0.5 - cy);
+            if dx * dx + dy * dy <= radius * radius {
+                let i = ((y * width + x) * 4) as usize;
+                rgba[i..i + 3].copy_from_slice(&color);
+                rgba[i + 3] = 255;
+            }
+        }
+    }
+}
+
+fn recent_label(text: &str) -> String {
+    let line = text.lines().next().unwrap_or_default().trim();
+    if line.chars().count() > RECENT_LABEL_CHARS {
+        let truncated: String = line.chars().take(RECENT_LABEL_CHARS - 1).collect();
+        format!("{}…", truncated)
+    } else {
+        line.to_string()
+    }
+}
+
+fn build_menu(app: &AppHandle) -> tauri::Result<Menu<tauri::Wry>> {
+    let s = settings::load_settings(app);
+    let menu = Menu::new(app)?;
+
+    menu.append(&MenuItem::with_id(
+        app,
+        "show",
+        "Open Murmure",
+        true,
+        None::<&str>,
+    )?)?;
+    menu.append(&PredefinedMenuItem::separator(app)?)?;
+
+    let suspended = app
+        .try_state::<TranscriptionSuspended>()
+        .is_some_and(|suspended| suspended.get());
+    menu.append(&CheckMenuItem::with_id(
+        app,
+        "toggle_suspend",
+        "Suspend transcription",
+        true,
+        suspended,
+        None::<&str>,
+    )?)?;
+
+    let microphones = Submenu::with_id(app, "microphones", "Microphone", true)?;
+    microphones.append(&CheckMenuItem::with_id(
+        app,
+        "microphone:",
+        "System default",
+        true,
+        s.microphone.is_none(),
+        None::<&str>,
+    )?)?;
+    for name in audio::list_microphones() {
+        let checked = s.microphone.as_deref() == Some(name.as_str());
+        microphones.append(&CheckMenuItem::with_id(
+            app,
+            format!("microphone:{}", name),
+            &name,
+            true,
+            checked,
+            None::<&str>,
+        )?)?;
+    }
+    menu.append(&microphones)?;
+
+    let profiles = Submenu::with_id(app, "profiles", "Output profile", true)?;
+    profiles.append(&CheckMenuItem::with_id(
+        app,
+        "profile:",
+        "Automatic",
+        true,
+        s.pinned_output_profile.is_none(),
+        None::<&str>,
+    )?)?;
+    for profile in &s.output_profiles {
+        let checked = s
+            .pinned_output_profile
+            .as_ref()
+            .is_some_and(|pinned| pinned.eq_ignore_ascii_case(&profile.name));
+        profiles.append(&CheckMenuItem::with_id(
+            app,
+            format!("profile:{}", profile.name),
+            &profile.name,
+            true,
+            checked,
+            None::<&str>,
+        )?)?;
+    }
+    menu.append(&profiles)?;
+
+    let api_running = app.state::<HttpApiState>().is_running();
+    menu.append(&CheckMenuItem::with_id(
+        app,
+        "toggle_api",
+        "HTTP API",
+        true,
+        api_running,
+        None::<&str>,
+    )?)?;
+
+    let recent = Submenu::with_id(app, "recent", "Recent transcriptions", true)?;
+    let entries = history::get_recent_transcriptions(app).unwrap_or_default();
+    if entries.is_empty() {
+        recent.append(&MenuItem::with_id(
+            app,
+            "recent_empty",
+            "No transcriptions yet",
+            false,
+            None::<&str>,
+        )?)?;
+    }
+    for entry in entries.iter().take(RECENT_TRANSCRIPTIONS) {
+        recent.append(&MenuItem::with_id(
+            app,
+            format!("history:{}", entry.id),
+            recent_label(&entry.text),
+            true,
+            None::<&str>,
+        )?)?;
+    }
+    menu.append(&recent)?;
+
+    menu.append(&PredefinedMenuItem::separator(app)?)?;
+    menu.append(&MenuItem::with_id(app, "quit", "Quit", true, None::<&str>)?)?;
+    Ok(menu)
+}
+
+/// Rebuild the menu from the current settings, history and API state.
+pub fn refresh_menu(app: &AppHandle) {
+    let Some(tray) = app.tray_by_id(TRAY_ID) else {
+        return;
+    };
+    match build_menu(app) {
+        Ok(menu) => {
+            if let Err(e) = tray.set_menu(Some(menu)) {
+                log::error!("Failed to update tray menu: {}", e);
             }
-            "quit" => {
-                app.exit(0);
+        }
+        Err(e) => log::error!("Failed to build tray menu: {}", e),
+    }
+}
+
+fn refresh_state(app: &AppHandle) {
+    let Some(tray) = app.tray_by_id(TRAY_ID) else {
+        return;
+    };
+    let state = TrayState::current(app);
+    let _ = tray.set_icon(state_icon(app, state));
+    let _ = tray.set_tooltip(Some(format!("Murmure - {}", state.label())));
+}
+
+fn show_main_window(app: &AppHandle) {
+    if let Some(window) = app.get_webview_window("main") {
+        let _ = window.show();
+        let _ = window.set_focus();
+    }
+}
+
+fn toggle_http_api(app: &AppHandle) {
+    let state = app.state::<HttpApiState>().inner().clone();
+    let mut s = settings::load_settings(app);
+    s.api_enabled = !state.is_running();
+    if s.api_enabled {
+        spawn_http_api_thread(app.clone(), s.api_port, state);
+    } else {
+        state.stop();
+    }
+    if let Err(e) = settings::save_settings(app, &s) {
+        log::error!("Failed to save HTTP API setting: {}", e);
+    }
+}
+
+fn set_microphone(app: &AppHandle, microphone: Option<String>) {
+    let mut s = settings::load_settings(app);
+    s.microphone = microphone;
+    if let Err(e) = settings::save_settings(app, &s) {
+        log::error!("Failed to switch microphone: {}", e);
+        return;
+    }
+    let _ = app.emit("microphone-changed", s.microphone);
+}
+
+fn copy_transcription(app: &AppHandle, id: u64) {
+    let entries = history::get_recent_transcriptions(app).unwrap_or_default();
+    let Some(entry) = entries.into_iter().find(|entry| entry.id == id) else {
+        return;
+    };
+    if let Err(e) = app.clipboard().write_text(entry.text) {
+        log::error!("Failed to copy transcription: {}", e);
+    }
+}
+
+fn on_menu_event(app: &AppHandle, id: &str) {
+    match id {
+        "show" => {
+            show_main_window(app);
+            return;
+        }
+        "quit" => {
+            app.exit(0);
+            return;
+        }
+        "toggle_suspend" => run_action(app, &ShortcutAction::ToggleSuspend),
+        "toggle_api" => toggle_http_api(app),
+        _ => {
+            if let Some(name) = id.strip_prefix("microphone:") {
+                set_microphone(app, Some(name.to_string()).filter(|n| !n.is_empty()));
+            } else if let Some(name) = id.strip_prefix("profile:") {
+                let profile = Some(name.to_string()).filter(|n| !n.is_empty());
+                run_action(app, &ShortcutAction::SwitchProfile(profile));
+            } else if let Some(entry_id) = id.strip_prefix("history:") {
+                if let Ok(entry_id) = entry_id.parse() {
+                    copy_transcription(app, entry_id);
+                }
             }
-            _ => {}
-        })
+        }
+    }
+    // Check items toggle themselves on click, put them back in line with the settings
+    let app = app.clone();
+    std::thread::spawn(move || refresh_menu(&app));
+}
+
+pub fn setup_tray(app: &AppHandle) -> Result<(), Box<dyn std::error::Error>> {
+    let menu = build_menu(app)?;
+
+    let builder = TrayIconBuilder::with_id(TRAY_ID)
+        .menu(&menu)
+        .tooltip(format!("Murmure - {}", TrayState::current(app).label()))
+        .on_menu_event(|app, event| on_menu_event(app, event.id.as_ref()))
         .on_tray_icon_event(|tray, event| {
             if let TrayIconEvent::Click {
                 button: tauri::tray::MouseButton::Left,
                 ..
             } = event
             {
-                let app = tray.app_handle();
-                if let Some(window) = app.get_webview_window("main") {
-                    let _ = window.show();
-                    let _ = window.set_focus();
-                }
+                show_main_window(tray.app_handle());
             }
         });
 
     #[cfg(target_os = "linux")]
-    let builder = builder.show_menu_on_left_click(true).icon_as_template(true);
-    #[cfg(target_os = "windows")]
-    let builder = builder;
+    let builder = builder.show_menu_on_left_click(true);
 
-    let _tray = builder
-        .icon(app.default_window_icon().unwrap().clone())
-        .build(app)?;
+    let icon = state_icon(app, TrayState::current(app)).ok_or("No app icon for the tray")?;
+    let _tray = builder.icon(icon).build(app)?;
+
+    for event in [
+        "transcription-state",
+        "recording-cancelled",
+        "transcription-suspended",
+    ] {
+        let app_handle = app.clone();
+        app.listen_any(event, move |_| refresh_state(&app_handle));
+    }
+    // Listing microphones can be slow, keep it off the thread that emitted the event
+    for event in [
+        "history-updated",
+        "transcription-suspended",
+        "output-profile-changed",
+        "microphone-changed",
+        "http-api-changed",
+    ] {
+        let app_handle = app.clone();
+        app.listen_any(event, move |_| {
+            let app_handle = app_handle.clone();
+            std::thread::spawn(move || refresh_menu(&app_handle));
+        });
+    }
 
     Ok(())
 }
@@ -0,0 +1,20 @@
+import { listen } from '@tauri-apps/api/event';
+import { useEffect } from 'react';
+import { toast } from 'sonner';
+
+interface ErrorEvent {
+    code: string;
+    message: string;
+}
+
+export const useErrorToast = () => {
+    useEffect(() => {
+        const unlistenPromise = listen<ErrorEvent>('error', (e) => {
+            // The same failure replaces its previous toast instead of stacking
+            toast.error(e.payload.message, { id: e.payload.code });
+        });
+        return () => {
+            unlistenPromise.then((unlisten) => unlisten());
+        };
+    }, []);
+};
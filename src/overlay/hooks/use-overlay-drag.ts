@@ -0,0 +1,58 @@
+import { invoke } from '@tauri-apps/api/core';
+import { getCurrentWindow } from '@tauri-apps/api/window';
+import React, { useEffect, useRef } from 'react';
+
+// The position is saved once the window has stopped moving for this long
+const SAVE_DELAY_MS = 300;
+
+export const useOverlayDrag = () => {
+    const dragging = useRef(false);
+    const moved = useRef(false);
+    const saveTimer = useRef<number | null>(null);
+
+    useEffect(() => {
+        const unlistenPromise = getCurrentWindow().onMoved(() => {
+            // The backend also moves the overlay, only a drag is saved
+            if (!dragging.current) {
+                return;
+            }
+            moved.current = true;
+            if (saveTimer.current) {
+                clearTimeout(saveTimer.current);
+            }
+            saveTimer.current = window.setTimeout(() => {
+                dragging.current = false;
+                moved.current = false;
+                saveTimer.current = null;
+                invoke('save_overlay_custom_position').catch((e) =>
+                    console.error('Failed to save overlay position:', e)
+                );
+            }, SAVE_DELAY_MS);
+        });
+        return () => {
+            unlistenPromise.then((unlisten) => unlisten());
+            if (saveTimer.current) {
+                clearTimeout(saveTimer.current);
+            }
+        };
+    }, []);
+
+    const onMouseDown = (e: React.MouseEvent) => {
+        if (e.button !== 0) {
+            return;
+        }
+        dragging.current = true;
+        getCurrentWindow()
+            .startDragging()
+            .catch((err) => console.error('Failed to drag overlay:', err));
+    };
+
+    // A click that did not move the window is not a drag
+    const onMouseUp = () => {
+        if (!moved.current) {
+            dragging.current = false;
+        }
+    };
+
+    return { onMouseDown, onMouseUp };
+};
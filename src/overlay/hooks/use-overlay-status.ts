@@ -0,0 +1,44 @@
+import { listen } from '@tauri-apps/api/event';
+import { useState, useEffect } from 'react';
+
+export type OverlayStatus = 'recording' | 'transcribing' | 'cancelled' | 'error';
+
+interface TranscriptionStatus {
+    state: 'recording' | 'transcribing' | 'done' | 'error';
+}
+
+interface ErrorEvent {
+    code: string;
+    message: string;
+}
+
+export const useOverlayStatus = () => {
+    const [status, setStatus] = useState<OverlayStatus>('recording');
+    const [error, setError] = useState<string | null>(null);
+
+    useEffect(() => {
+        const unlistenShow = listen<OverlayStatus>('show-overlay', (e) => {
+            setStatus(e.payload);
+        });
+        // An overlay that stays visible is not shown again when a recording starts
+        const unlistenState = listen<TranscriptionStatus>(
+            'transcription-state',
+            (e) => {
+                if (e.payload.state === 'recording') {
+                    setStatus('recording');
+                    setError(null);
+                }
+            }
+        );
+        const unlistenError = listen<ErrorEvent>('error', (e) => {
+            setError(e.payload.message);
+        });
+        return () => {
+            unlistenShow.then((unlisten) => unlisten());
+            unlistenState.then((unlisten) => unlisten());
+            unlistenError.then((unlisten) => unlisten());
+        };
+    }, []);
+
+    return { status, error };
+};
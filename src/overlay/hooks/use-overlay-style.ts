@@ -0,0 +1,22 @@
+import { invoke } from '@tauri-apps/api/core';
+import { listen } from '@tauri-apps/api/event';
+import { useState, useEffect } from 'react';
+
+export const useOverlayStyle = () => {
+    const [expanded, setExpanded] = useState(false);
+
+    useEffect(() => {
+        invoke<string>('get_overlay_style')
+            .then((style) => setExpanded(style === 'expanded'))
+            .catch((e) => console.error('Failed to load overlay style:', e));
+
+        const unlistenPromise = listen<string>('overlay-style-changed', (e) =>
+            setExpanded(e.payload === 'expanded')
+        );
+        return () => {
+            unlistenPromise.then((unlisten) => unlisten());
+        };
+    }, []);
+
+    return { expanded };
+};
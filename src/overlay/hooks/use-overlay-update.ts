@@ -0,0 +1,47 @@
+import { listen } from '@tauri-apps/api/event';
+import { useState, useEffect } from 'react';
+
+type OverlayUpdate =
+    | { kind: 'elapsed'; seconds: number }
+    | { kind: 'partial'; text: string }
+    | { kind: 'transcribing' }
+    | { kind: 'finished'; text: string };
+
+export type TranscriptPhase = 'recording' | 'transcribing' | 'finished';
+
+export const useOverlayUpdate = () => {
+    const [seconds, setSeconds] = useState(0);
+    const [text, setText] = useState('');
+    const [phase, setPhase] = useState<TranscriptPhase>('recording');
+
+    useEffect(() => {
+        const unlistenPromise = listen<OverlayUpdate>('overlay-update', (e) => {
+            const update = e.payload;
+            switch (update.kind) {
+                case 'elapsed':
+                    // A new recording starts from an empty transcript
+                    if (update.seconds === 0) {
+                        setText('');
+                    }
+                    setSeconds(update.seconds);
+                    setPhase('recording');
+                    break;
+                case 'partial':
+                    setText(update.text);
+                    break;
+                case 'transcribing':
+                    setPhase('transcribing');
+                    break;
+                case 'finished':
+                    setText(update.text);
+                    setPhase('finished');
+                    break;
+            }
+        });
+        return () => {
+            unlistenPromise.then((unlisten) => unlisten());
+        };
+    }, []);
+
+    return { seconds, text, phase };
+};
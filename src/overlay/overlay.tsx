@@ -1,9 +1,42 @@
 import React from 'react';
+import clsx from 'clsx';
 import { AudioVisualizer } from '@/features/home/audio-visualizer/audio-visualizer';
+import { useOverlayDrag } from './hooks/use-overlay-drag';
+import { OverlayStatus, useOverlayStatus } from './hooks/use-overlay-status';
+import { useOverlayStyle } from './hooks/use-overlay-style';
+import { useOverlayUpdate } from './hooks/use-overlay-update';
 
-export const Overlay: React.FC = () => {
-    return (
-        <div className="w-[80px] h-[18px] bg-black/70 rounded-sm flex items-center justify-center select-none overflow-hidden">
+const formatElapsed = (seconds: number) => {
+    const minutes = Math.floor(seconds / 60);
+    return `${minutes}:${String(seconds % 60).padStart(2, '0')}`;
+};
+
+const StatusLabel = ({ status }: { status: OverlayStatus }) => (
+    <span
+        className={clsx(
+            'text-[10px] leading-none',
+            status === 'transcribing' && 'text-zinc-300 animate-pulse',
+            status === 'cancelled' && 'text-zinc-400',
+            status === 'error' && 'text-red-400'
+        )}
+    >
+        {status === 'transcribing' && 'Transcribing'}
+        {status === 'cancelled' && 'Cancelled'}
+        {status === 'error' && 'Error'}
+    </span>
+);
+
+interface OverlayProps {
+    status: OverlayStatus;
+    error: string | null;
+}
+
+const CompactOverlay = ({ status, error }: OverlayProps) => (
+    <div
+        className="w-[80px] h-[18px] bg-black/70 rounded-sm flex items-center justify-center select-none overflow-hidden"
+        title={status === 'error' ? (error ?? undefined) : undefined}
+    >
+        {status === 'recording' ? (
             <div className="origin-center">
                 <AudioVisualizer
                     bars={14}
@@ -12,6 +45,75 @@ export const Overlay: React.FC = () => {
                     audioPixelHeight={2}
                 />
             </div>
+        ) : (
+            <StatusLabel status={status} />
+        )}
+    </div>
+);
+
+// Sizes match `expanded_height` in overlay.rs: 12px of vertical padding and a
+// 28px header, then up to 4 lines of 18px, in a 360px wide window
+const ExpandedOverlay = ({ status, error }: OverlayProps) => {
+    const { seconds, text, phase } = useOverlayUpdate();
+    const finished = phase === 'finished' && status !== 'error';
+    const label: OverlayStatus =
+        status === 'recording' && phase === 'transcribing'
+            ? 'transcribing'
+            : status;
+
+    return (
+        <div className="w-[360px] px-3 py-1.5 bg-black/70 rounded-md select-none overflow-hidden">
+            <div className="h-[28px] flex items-center justify-between">
+                {finished ? (
+                    <span />
+                ) : label === 'recording' ? (
+                    <div className="w-[80px]">
+                        <AudioVisualizer
+                            bars={14}
+                            rows={9}
+                            audioPixelWidth={2}
+                            audioPixelHeight={2}
+                        />
+                    </div>
+                ) : (
+                    <StatusLabel status={label} />
+                )}
+                <span className="text-[10px] leading-none text-zinc-400 tabular-nums">
+                    {finished ? 'Done' : formatElapsed(seconds)}
+                </span>
+            </div>
+            {status === 'error' && error ? (
+                <p className="font-mono text-[12px] leading-[18px] text-red-300 line-clamp-4 break-words">
+                    {error}
+                </p>
+            ) : (
+                text && (
+                    <p className="font-mono text-[12px] leading-[18px] text-white line-clamp-4 break-words">
+                        {text}
+                    </p>
+                )
+            )}
+        </div>
+    );
+};
+
+export const Overlay: React.FC = () => {
+    const { status, error } = useOverlayStatus();
+    const { expanded } = useOverlayStyle();
+    const { onMouseDown, onMouseUp } = useOverlayDrag();
+
+    // Dragging the overlay moves it to a custom position
+    return (
+        <div
+            className="w-fit cursor-move"
+            onMouseDown={onMouseDown}
+            onMouseUp={onMouseUp}
+        >
+            {expanded ? (
+                <ExpandedOverlay status={status} error={error} />
+            ) : (
+                <CompactOverlay status={status} error={error} />
+            )}
         </div>
     );
 };
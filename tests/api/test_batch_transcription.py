@@ -0,0 +1,84 @@
+#!/usr/bin/env python3
+"""
+Send WAV files to the batch endpoint, then one by one to the single-file
+endpoint, and compare the texts. Batching pads clips to the same length,
+so both should agree.
+
+Usage:
+    python test_batch_transcription.py memo-1.wav memo-2.wav        # Port 4800
+    python test_batch_transcription.py --port 4801 memos/*.wav
+"""
+
+import json
+import os
+import sys
+import time
+import urllib.error
+import urllib.request
+import uuid
+
+args = sys.argv[1:]
+PORT = 4800
+if len(args) >= 2 and args[0] == "--port":
+    PORT = int(args[1])
+    args = args[2:]
+if not args:
+    print(__doc__)
+    sys.exit(1)
+
+BASE_URL = f"http://127.0.0.1:{PORT}/api/transcribe"
+
+
+def post_files(url, paths):
+    boundary = uuid.uuid4().hex
+    body = b""
+    for path in paths:
+        with open(path, "rb") as f:
+            data = f.read()
+        name = os.path.basename(path)
+        body += (
+            f"--{boundary}\r\n"
+            f'Content-Disposition: form-data; name="audio"; filename="{name}"\r\n'
+            "Content-Type: audio/wav\r\n\r\n"
+        ).encode() + data + b"\r\n"
+    body += f"--{boundary}--\r\n".encode()
+
+    request = urllib.request.Request(url, data=body, method="POST")
+    request.add_header("Content-Type", f"multipart/form-data; boundary={boundary}")
+    try:
+        with urllib.request.urlopen(request) as response:
+            return json.load(response)
+    except urllib.error.HTTPError as e:
+        return json.load(e)
+
+
+def main():
+    started = time.time()
+    batch = post_files(f"{BASE_URL}/batch", args)
+    batch_seconds = time.time() - started
+    if "results" not in batch:
+        print(f"Batch request failed: {batch.get('error')}")
+        sys.exit(1)
+
+    started = time.time()
+    singles = [post_files(BASE_URL, [path]) for path in args]
+    single_seconds = time.time() - started
+
+    mismatches = 0
+    for path, item, single in zip(args, batch["results"], singles):
+        if "error" in item:
+            print(f"{path}: error in batch: {item['error']}")
+            continue
+        same = item["text"] == single.get("text")
+        mismatches += not same
+        print(f"{path}: {'OK' if same else 'DIFFERENT'}")
+        if not same:
+            print(f"  batch:  {item['text']}")
+            print(f"  single: {single.get('text', single.get('error'))}")
+
+    print(f"\nBatch: {batch_seconds:.1f}s, one by one: {single_seconds:.1f}s")
+    sys.exit(1 if mismatches else 0)
+
+
+if __name__ == "__main__":
+    main()
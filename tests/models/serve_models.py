@@ -0,0 +1,112 @@
+#!/usr/bin/env python3
+"""
+Local stand-in for the model download source.
+Serves the .tar.gz archives of a folder along with a manifest.json listing them,
+and honors Range requests so resumed downloads can be tested.
+
+Usage:
+    python serve_models.py ./archives          # Serve on port 8765
+    python serve_models.py ./archives 9000     # Serve on port 9000
+    python serve_models.py ./archives 8765 --throttle   # Slow down to test resuming
+"""
+
+import hashlib
+import http.server
+import json
+import os
+import re
+import socketserver
+import sys
+import time
+
+if len(sys.argv) < 2:
+    print(__doc__)
+    sys.exit(1)
+
+ROOT = os.path.abspath(sys.argv[1])
+PORT = int(sys.argv[2]) if len(sys.argv) > 2 and sys.argv[2].isdigit() else 8765
+THROTTLE = "--throttle" in sys.argv
+CHUNK_SIZE = 64 * 1024
+
+
+def sha256(path):
+    digest = hashlib.sha256()
+    with open(path, "rb") as f:
+        for block in iter(lambda: f.read(1024 * 1024), b""):
+            digest.update(block)
+    return digest.hexdigest()
+
+
+def build_manifest():
+    models = []
+    for file_name in sorted(os.listdir(ROOT)):
+        if not file_name.endswith(".tar.gz"):
+            continue
+        path = os.path.join(ROOT, file_name)
+        models.append({
+            "name": file_name[: -len(".tar.gz")],
+            "url": file_name,
+            "sha256": sha256(path),
+            "size": os.path.getsize(path),
+            "languages": [],
+        })
+    return json.dumps(models, indent=2).encode()
+
+
+class ModelHandler(http.server.BaseHTTPRequestHandler):
+    def do_GET(self):
+        name = self.path.lstrip("/")
+        if name == "manifest.json":
+            body = build_manifest()
+            self.send_response(200)
+            self.send_header("Content-Type", "application/json")
+            self.send_header("Content-Length", str(len(body)))
+            self.end_headers()
+            self.wfile.write(body)
+            return
+
+        path = os.path.join(ROOT, os.path.basename(name))
+        if not os.path.isfile(path):
+            self.send_error(404)
+            return
+
+        size = os.path.getsize(path)
+        start = 0
+        match = re.match(r"bytes=(\d+)-$", self.headers.get("Range", ""))
+        if match:
+            start = int(match.group(1))
+            if start >= size:
+                self.send_response(416)
+                self.send_header("Content-Range", f"bytes */{size}")
+                self.end_headers()
+                return
+            self.send_response(206)
+            self.send_header("Content-Range", f"bytes {start}-{size - 1}/{size}")
+        else:
+            self.send_response(200)
+        self.send_header("Content-Type", "application/gzip")
+        self.send_header("Content-Length", str(size - start))
+        self.send_header("Accept-Ranges", "bytes")
+        self.end_headers()
+
+        with open(path, "rb") as f:
+            f.seek(start)
+            for block in iter(lambda: f.read(CHUNK_SIZE), b""):
+                self.wfile.write(block)
+                if THROTTLE:
+                    time.sleep(0.05)
+
+
+def main():
+    socketserver.TCPServer.allow_reuse_address = True
+    with socketserver.ThreadingTCPServer(("127.0.0.1", PORT), ModelHandler) as httpd:
+        print(f"Serving models from {ROOT} on http://127.0.0.1:{PORT}")
+        print("Press Ctrl+C to stop")
+        try:
+            httpd.serve_forever()
+        except KeyboardInterrupt:
+            pass
+
+
+if __name__ == "__main__":
+    main()